@@ -0,0 +1,193 @@
+use std::fmt::Write;
+use std::time::Duration;
+
+use crate::error::ParseError;
+use crate::solution::Runner;
+
+pub const PHASES: [&str; 3] = ["parse", "part1", "part2"];
+
+#[derive(Debug, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub p95: Duration,
+}
+
+/// nearest-rank percentile of already sorted samples
+fn percentile(sorted: &[Duration], pct: usize) -> Duration {
+    let rank = (pct * sorted.len()).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+pub fn stats(mut samples: Vec<Duration>) -> Stats {
+    samples.sort();
+
+    Stats {
+        min: samples[0],
+        median: percentile(&samples, 50),
+        p95: percentile(&samples, 95),
+    }
+}
+
+#[derive(Debug)]
+pub struct Row {
+    pub day: u32,
+    pub phase: &'static str,
+    pub iterations: usize,
+    pub stats: Stats,
+}
+
+/// times every phase `iterations` times, after `warmup` runs that aren't counted
+pub fn bench_day(
+    runner: &dyn Runner,
+    data: &str,
+    warmup: usize,
+    iterations: usize,
+) -> Result<Vec<Row>, ParseError> {
+    for _ in 0..warmup {
+        runner.time_phases(data)?;
+    }
+
+    let mut samples: [Vec<Duration>; 3] = Default::default();
+    for _ in 0..iterations.max(1) {
+        let durations = runner.time_phases(data)?;
+        samples
+            .iter_mut()
+            .zip(durations)
+            .for_each(|(s, d)| s.push(d));
+    }
+
+    Ok(PHASES
+        .into_iter()
+        .zip(samples)
+        .map(|(phase, samples)| Row {
+            day: runner.day(),
+            phase,
+            iterations: samples.len(),
+            stats: stats(samples),
+        })
+        .collect())
+}
+
+pub fn to_table(rows: &[Row]) -> String {
+    let mut out = format!(
+        "{:>3}  {:<5}  {:>10}  {:>10}  {:>10}\n",
+        "day", "phase", "min", "median", "p95"
+    );
+    rows.iter().for_each(|r| {
+        let fmt = |d: Duration| format!("{d:.2?}");
+        writeln!(
+            out,
+            "{:>3}  {:<5}  {:>10}  {:>10}  {:>10}",
+            r.day,
+            r.phase,
+            fmt(r.stats.min),
+            fmt(r.stats.median),
+            fmt(r.stats.p95)
+        )
+        .unwrap();
+    });
+    out
+}
+
+/// durations are in nanoseconds
+pub fn to_csv(rows: &[Row]) -> String {
+    let mut out = String::from("day,phase,iterations,min_ns,median_ns,p95_ns\n");
+    rows.iter().for_each(|r| {
+        writeln!(
+            out,
+            "{},{},{},{},{},{}",
+            r.day,
+            r.phase,
+            r.iterations,
+            r.stats.min.as_nanos(),
+            r.stats.median.as_nanos(),
+            r.stats.p95.as_nanos()
+        )
+        .unwrap();
+    });
+    out
+}
+
+/// durations are in nanoseconds
+pub fn to_json(rows: &[Row]) -> String {
+    let objects = rows
+        .iter()
+        .map(|r| {
+            format!(
+                r#"  {{"day": {}, "phase": "{}", "iterations": {}, "min_ns": {}, "median_ns": {}, "p95_ns": {}}}"#,
+                r.day,
+                r.phase,
+                r.iterations,
+                r.stats.min.as_nanos(),
+                r.stats.median.as_nanos(),
+                r.stats.p95.as_nanos()
+            )
+        })
+        .collect::<Vec<_>>();
+
+    format!("[\n{}\n]\n", objects.join(",\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::solution;
+
+    use super::*;
+
+    fn ms(n: u64) -> Duration {
+        Duration::from_millis(n)
+    }
+
+    #[test]
+    fn t_stats() {
+        let s = stats((1..=20).rev().map(ms).collect());
+        assert_eq!(
+            s,
+            Stats {
+                min: ms(1),
+                median: ms(10),
+                p95: ms(19)
+            }
+        );
+
+        let s = stats(vec![ms(7)]);
+        assert_eq!((s.min, s.median, s.p95), (ms(7), ms(7), ms(7)));
+    }
+
+    #[test]
+    fn t_output() {
+        let rows = vec![Row {
+            day: 3,
+            phase: "part1",
+            iterations: 5,
+            stats: Stats {
+                min: ms(1),
+                median: ms(2),
+                p95: ms(3),
+            },
+        }];
+
+        assert_eq!(
+            to_csv(&rows),
+            "day,phase,iterations,min_ns,median_ns,p95_ns\n3,part1,5,1000000,2000000,3000000\n"
+        );
+        assert_eq!(
+            to_json(&rows),
+            "[\n  {\"day\": 3, \"phase\": \"part1\", \"iterations\": 5, \"min_ns\": 1000000, \
+             \"median_ns\": 2000000, \"p95_ns\": 3000000}\n]\n"
+        );
+    }
+
+    #[test]
+    fn t() {
+        let rows = bench_day(solution::get(1).unwrap(), "1abc2\n", 1, 3).unwrap();
+        assert_eq!(
+            rows.iter().map(|r| r.phase).collect::<Vec<_>>(),
+            PHASES.to_vec()
+        );
+        assert!(rows.iter().all(|r| r.day == 1 && r.iterations == 3));
+
+        assert!(bench_day(solution::get(7).unwrap(), "nope\n", 0, 1).is_err());
+    }
+}
@@ -0,0 +1,103 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::io::Read;
+use std::str::FromStr;
+use std::{fs, io};
+
+/// a subcommand followed by `--name value` flags. a flag with no value (end of args, or
+/// followed by another flag) is stored as "true"
+pub struct Args {
+    pub command: Option<String>,
+    flags: HashMap<String, String>,
+}
+
+impl Args {
+    pub fn parse<I>(args: I) -> Result<Args, Box<dyn Error>>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut args = args.into_iter().peekable();
+
+        let command = args.next_if(|a| !a.starts_with("--"));
+
+        let mut flags = HashMap::new();
+        while let Some(arg) = args.next() {
+            let name = arg
+                .strip_prefix("--")
+                .ok_or_else(|| format!("unexpected argument '{arg}'"))?;
+            let value = args
+                .next_if(|a| !a.starts_with("--"))
+                .unwrap_or_else(|| String::from("true"));
+            flags.insert(String::from(name), value);
+        }
+
+        Ok(Args { command, flags })
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.flags.get(name).map(|s| s.as_str())
+    }
+
+    pub fn get_parsed<T>(&self, name: &str) -> Result<Option<T>, Box<dyn Error>>
+    where
+        T: FromStr,
+        T::Err: Error + 'static,
+    {
+        self.get(name)
+            .map(|v| {
+                v.parse()
+                    .map_err(|e| format!("invalid value '{v}' for --{name}: {e}").into())
+            })
+            .transpose()
+    }
+
+    pub fn required<T>(&self, name: &str) -> Result<T, Box<dyn Error>>
+    where
+        T: FromStr,
+        T::Err: Error + 'static,
+    {
+        self.get_parsed(name)?
+            .ok_or_else(|| format!("missing required flag --{name}").into())
+    }
+
+    /// reads the file named by `--input`, or stdin if it's missing or "-"
+    pub fn read_input(&self) -> Result<String, Box<dyn Error>> {
+        match self.get("input") {
+            None | Some("-") => {
+                let mut data = String::new();
+                io::stdin().read_to_string(&mut data)?;
+                Ok(data)
+            }
+            Some(fname) => {
+                fs::read_to_string(fname).map_err(|e| format!("couldn't read {fname}: {e}").into())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &str) -> Args {
+        Args::parse(s.split_whitespace().map(String::from)).unwrap()
+    }
+
+    #[test]
+    fn t() {
+        let a = args("run --day 7 --part 2 --input foo.txt");
+        assert_eq!(a.command.as_deref(), Some("run"));
+        assert_eq!(a.required::<u32>("day").unwrap(), 7);
+        assert_eq!(a.get_parsed::<u32>("part").unwrap(), Some(2));
+        assert_eq!(a.get("input"), Some("foo.txt"));
+        assert!(a.get("missing").is_none());
+        assert!(a.required::<u32>("missing").is_err());
+
+        let a = args("--day x --flag");
+        assert!(a.command.is_none());
+        assert!(a.required::<u32>("day").is_err());
+        assert_eq!(a.get("flag"), Some("true"));
+
+        assert!(Args::parse(["run", "oops"].map(String::from)).is_err());
+    }
+}
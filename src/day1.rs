@@ -1,4 +1,6 @@
-use crate::util::{get_non_empty_lines, DataLine};
+use crate::error::ParseError;
+use crate::solution::Solution;
+use crate::util::{get_non_empty_lines, par_map, DataLine};
 use regex::Regex;
 
 const NUM_STRS: [&str; 9] = [
@@ -13,61 +15,105 @@ fn convert_num(num: &str) -> u64 {
     }
 }
 
-fn match_and_convert<'a, I>(re: &Regex, strs_to_test: I) -> u64
+fn match_and_convert<'a, I>(re: &Regex, strs_to_test: I) -> Option<u64>
 where
     I: IntoIterator<Item = &'a str>,
 {
-    convert_num(
-        strs_to_test
-            .into_iter()
-            .find_map(|s| re.find(s))
-            .unwrap()
-            .as_str(),
-    )
+    strs_to_test
+        .into_iter()
+        .find_map(|s| re.find(s))
+        .map(|m| convert_num(m.as_str()))
 }
 
-fn first_num(from: &str, re: &Regex) -> u64 {
+fn first_num(from: &str, re: &Regex) -> Option<u64> {
     match_and_convert(re, (1..from.len() + 1).map(|i| &from[0..i]))
 }
 
-fn last_num(from: &str, re: &Regex) -> u64 {
+fn last_num(from: &str, re: &Regex) -> Option<u64> {
     let len = from.len();
     match_and_convert(re, (0..len).rev().map(|i| &from[i..len]))
 }
 
 fn get_first_and_last_as_num(dl: &DataLine, re: &Regex) -> Option<u64> {
-    let first = first_num(dl.line, re);
-    let last = last_num(dl.line, re);
-    let num_str = format!("{first}{last}");
-    num_str.parse().ok()
+    Some(first_num(dl.line, re)? * 10 + last_num(dl.line, re)?)
 }
 
-fn doit_impl(data: &str, re: Regex) -> u64 {
-    get_non_empty_lines(data).fold(0_u64, |acc, line| {
-        acc + get_first_and_last_as_num(&line, &re).unwrap_or_else(|| panic!("{line}"))
+fn build_str_num_regex() -> Regex {
+    Regex::new(&format!("\\d|{}", NUM_STRS.join("|"))).unwrap()
+}
+
+fn parse_lines(data: &str) -> Vec<DataLine<'_>> {
+    get_non_empty_lines(data).collect()
+}
+
+/// every line has to have a number in it, the way `re` finds them
+fn check_lines(lines: &[DataLine], re: &Regex, expected: &str) -> Result<(), ParseError> {
+    match lines.iter().find(|line| !re.is_match(line.line)) {
+        Some(line) => Err(line.unexpected(line.line, expected)),
+        None => Ok(()),
+    }
+}
+
+/// what `check_lines` already made sure of
+const LINES_CHECKED: &str = "every line was checked for a number first";
+
+fn doit_impl(lines: &[DataLine], re: Regex) -> u64 {
+    par_map(lines, |line| {
+        get_first_and_last_as_num(line, &re).expect(LINES_CHECKED)
     })
+    .into_iter()
+    .sum()
 }
 
-fn doit(data: &str) -> u64 {
-    doit_impl(data, Regex::new(r"\d").unwrap())
+fn doit(lines: &[DataLine]) -> u64 {
+    doit_impl(lines, Regex::new(r"\d").unwrap())
 }
 
-fn build_str_num_regex() -> Regex {
-    Regex::new(&format!("\\d|{}", NUM_STRS.join("|"))).unwrap()
+fn doit2(lines: &[DataLine]) -> u64 {
+    doit_impl(lines, build_str_num_regex())
 }
 
-fn doit2(data: &str) -> u64 {
-    doit_impl(data, build_str_num_regex())
+pub struct Day1;
+
+impl Solution for Day1 {
+    const DAY: u32 = 1;
+
+    type Input<'a> = Vec<DataLine<'a>>;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(data: &str) -> Result<Self::Input<'_>, ParseError> {
+        Ok(parse_lines(data))
+    }
+
+    fn check1(input: &Self::Input<'_>) -> Result<(), ParseError> {
+        check_lines(input, &Regex::new(r"\d").unwrap(), "a digit")
+    }
+
+    fn check2(input: &Self::Input<'_>) -> Result<(), ParseError> {
+        check_lines(input, &build_str_num_regex(), "a digit or its name")
+    }
+
+    fn part1(input: &Self::Input<'_>) -> u64 {
+        doit(input)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> u64 {
+        doit2(input)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::solution::Runner;
     use crate::util::read_file_panic;
+    use crate::verify::recorded_answer;
 
     #[test]
     fn t1() {
         let data = &read_file_panic("./data/day1/part1/test.txt");
+        let data = &parse_lines(data);
         let answer = doit(data);
         assert_eq!(answer, 142);
     }
@@ -75,17 +121,39 @@ mod tests {
     #[test]
     fn t2() {
         let data = &read_file_panic("./data/day1/part2/test.txt");
+        let data = &parse_lines(data);
         let answer = doit2(data);
         assert_eq!(answer, 281);
     }
 
+    #[test]
+    fn bad_input() {
+        let err = |data, part| Day1.run(data, part).unwrap_err().to_string();
+
+        assert_eq!(
+            err("1abc2\n\nabc\n", 1),
+            r#"Line 3, column 1: expected a digit, found "abc""#
+        );
+        // spelling it out is only good enough for part 2
+        assert_eq!(
+            err("a1b2\neightwo\n", 1),
+            r#"Line 2, column 1: expected a digit, found "eightwo""#
+        );
+        assert_eq!(Day1.run("a1b2\neightwo\n", 2), Ok(Some(String::from("94"))));
+        assert_eq!(
+            err("a1b2\nxyz\n", 2),
+            r#"Line 2, column 1: expected a digit or its name, found "xyz""#
+        );
+    }
+
     #[test]
     fn d() {
         let data = &read_file_panic("./data/day1/data.txt");
+        let data = &parse_lines(data);
         let answer = doit(data);
-        assert_eq!(answer, 55002);
+        assert_eq!(answer.to_string(), recorded_answer(1, 1));
 
         let answer = doit2(data);
-        assert_eq!(answer, 55093);
+        assert_eq!(answer.to_string(), recorded_answer(1, 2));
     }
 }
@@ -1,67 +1,80 @@
-use crate::day10::Cardinal::{East, North, South, West};
-use crate::util::get_non_empty_lines;
-
-type XPos = usize;
-type YPos = usize;
-type Coord = (XPos, YPos);
-type Grid = Vec<Vec<char>>;
-type GridAndStartPoint = (Grid, Coord);
-
-#[derive(Copy, Clone, Debug, PartialEq)]
-enum Cardinal {
-    North,
-    South,
-    East,
-    West,
-}
-
-fn north(c: Coord) -> Option<Coord> {
-    let (x, y) = c;
-    if y > 0 {
-        Some((x, y - 1))
-    } else {
-        None
-    }
-}
-fn south(c: Coord) -> Option<Coord> {
-    let (x, y) = c;
-    Some((x, y + 1))
+use std::collections::HashSet;
+use std::fmt;
+use std::fmt::Formatter;
+
+use crate::error::{not_found, ParseError};
+use crate::solution::Solution;
+use crate::util::Dir::{East, North, South, West};
+use crate::util::{get_non_empty_lines, Dir, Grid, Pos};
+
+type Coord = Pos;
+pub type GridAndStartPoint = (Grid<char>, Coord);
+
+#[derive(Debug, PartialEq)]
+pub enum PipeError {
+    MissingStart,
+    /// a start needs exactly two neighbors with pipes leading back into it
+    StartConnections {
+        at: Coord,
+        count: usize,
+    },
+    /// the pipe at `at` doesn't connect back to the one we came from
+    Broken {
+        at: Coord,
+    },
+    /// the pipe at `at` leads off the edge of the grid
+    LeavesGrid {
+        at: Coord,
+    },
+    /// a loop can't start at `at` because there's no pipe there
+    NoPipe {
+        at: Coord,
+    },
 }
-fn west(c: Coord) -> Option<Coord> {
-    let (x, y) = c;
-    if x > 0 {
-        Some((x - 1, y))
-    } else {
-        None
+
+impl PipeError {
+    fn at(&self) -> Option<Coord> {
+        match *self {
+            PipeError::MissingStart => None,
+            PipeError::StartConnections { at, .. }
+            | PipeError::Broken { at }
+            | PipeError::LeavesGrid { at }
+            | PipeError::NoPipe { at } => Some(at),
+        }
     }
 }
-fn east(c: Coord) -> Option<Coord> {
-    let (x, y) = c;
-    Some((x + 1, y))
-}
-fn neighbor(c: Coord, dir: Cardinal) -> Option<Coord> {
-    match dir {
-        North => north(c),
-        South => south(c),
-        East => east(c),
-        West => west(c),
+
+impl fmt::Display for PipeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            PipeError::MissingStart => write!(f, "there's no starting point 'S'"),
+            PipeError::StartConnections { at: (x, y), count } => write!(
+                f,
+                "the start at {x},{y} connects to {count} pipes instead of 2"
+            ),
+            PipeError::Broken { at: (x, y) } => write!(f, "the loop breaks at {x},{y}"),
+            PipeError::LeavesGrid { at: (x, y) } => {
+                write!(f, "the loop leaves the grid at {x},{y}")
+            }
+            PipeError::NoPipe { at: (x, y) } => write!(f, "there's no pipe at {x},{y}"),
+        }
     }
 }
 
-fn pipe_for(dir_a: Cardinal, dir_b: Cardinal) -> char {
+impl std::error::Error for PipeError {}
+
+fn pipe_for(dir_a: Dir, dir_b: Dir) -> Option<char> {
     match (dir_a, dir_b) {
-        (North, South) | (South, North) => '|',
-        (East, West) | (West, East) => '-',
-        (North, East) | (East, North) => 'L',
-        (North, West) | (West, North) => 'J',
-        (South, West) | (West, South) => '7',
-        (South, East) | (East, South) => 'F',
-        (_, _) => {
-            panic!("couldn't find pipe")
-        }
+        (North, South) | (South, North) => Some('|'),
+        (East, West) | (West, East) => Some('-'),
+        (North, East) | (East, North) => Some('L'),
+        (North, West) | (West, North) => Some('J'),
+        (South, West) | (West, South) => Some('7'),
+        (South, East) | (East, South) => Some('F'),
+        (_, _) => None,
     }
 }
-fn connects(c: char) -> Option<(Cardinal, Cardinal)> {
+fn connects(c: char) -> Option<(Dir, Dir)> {
     match c {
         '|' => Some((North, South)),
         '-' => Some((East, West)),
@@ -73,85 +86,117 @@ fn connects(c: char) -> Option<(Cardinal, Cardinal)> {
     }
 }
 
-fn opposite(dir: Cardinal) -> Cardinal {
-    match dir {
-        North => South,
-        South => North,
-        East => West,
-        West => East,
-    }
-}
-
-fn connects_to(c: char, dir: Cardinal) -> bool {
+fn connects_to(c: char, dir: Dir) -> bool {
     connects(c).iter().any(|(a, b)| *a == dir || *b == dir)
 }
 
-fn char_at(grid: &Grid, coord: Coord) -> Option<char> {
-    let (x, y) = coord;
-    let line = grid.get(y)?;
-    let c = line.get(x)?;
-    Some(*c)
-}
-
-fn replace_start_point((mut grid, sp): GridAndStartPoint) -> GridAndStartPoint {
-    let at_north = north(sp).into_iter().flat_map(|c| char_at(&grid, c)).next();
-    let at_south = south(sp).into_iter().flat_map(|c| char_at(&grid, c)).next();
-    let at_east = east(sp).into_iter().flat_map(|c| char_at(&grid, c)).next();
-    let at_west = west(sp).into_iter().flat_map(|c| char_at(&grid, c)).next();
-
-    let get_connects = |c: Option<char>, d| c.filter(|&c| connects_to(c, d)).map(|_| opposite(d));
-
-    let north_connects = get_connects(at_north, South);
-    let south_connects = get_connects(at_south, North);
-    let east_connects = get_connects(at_east, West);
-    let west_connects = get_connects(at_west, East);
-
-    let connections = vec![north_connects, south_connects, east_connects, west_connects]
+fn replace_start_point((mut grid, sp): GridAndStartPoint) -> Result<GridAndStartPoint, PipeError> {
+    // the directions out of the start point whose neighbor has a pipe pointing back at it
+    let connections = Dir::ALL
         .into_iter()
-        .flatten()
+        .filter(|&d| {
+            grid.step(sp, d)
+                .is_some_and(|c| connects_to(grid[c], d.opposite()))
+        })
         .collect::<Vec<_>>();
 
-    let a = &connections[0];
-    let b = &connections[1];
-
-    let pipe = pipe_for(*a, *b);
-
-    let (x, y) = sp;
-    grid[y][x] = pipe;
+    grid[sp] = match connections[..] {
+        [a, b] => pipe_for(a, b),
+        _ => None,
+    }
+    .ok_or(PipeError::StartConnections {
+        at: sp,
+        count: connections.len(),
+    })?;
 
-    (grid, sp)
+    Ok((grid, sp))
 }
 
-fn parse_grid(data: &str) -> GridAndStartPoint {
-    replace_start_point(
-        get_non_empty_lines(data).fold((vec![], (0, 0)), |acc, line| {
-            let (mut grid, sp) = acc;
+/// the tiles, along with which line each row came from, for errors
+fn parse_tiles(data: &str) -> Result<(Grid<char>, Vec<usize>), ParseError> {
+    let mut row_lines = vec![];
+    let grid = Grid::parse_lines(get_non_empty_lines(data), |line, col, c| {
+        if col == 0 {
+            row_lines.push(line.line_number);
+        }
+        if "|-LJ7F.S".contains(c) {
+            Ok(c)
+        } else {
+            Err(line.unexpected(line.char_at(col), "a pipe, '.' or 'S'"))
+        }
+    })?;
+    Ok((grid, row_lines))
+}
 
-            let this_line = line.line.chars().collect::<Vec<_>>();
+fn start_point(grid: &Grid<char>) -> Option<Coord> {
+    grid.iter().find(|&(_, &c)| c == 'S').map(|(pos, _)| pos)
+}
 
-            let new_sp_opt = (0..this_line.len())
-                .find(|&i| this_line[i] == 'S')
-                .map(|x| (x, line.line_number));
+pub fn parse_grid(data: &str) -> Result<GridAndStartPoint, ParseError> {
+    let (grid, row_lines) = parse_tiles(data)?;
+    let sp = start_point(&grid);
+
+    let checked = sp
+        .ok_or(PipeError::MissingStart)
+        .and_then(|sp| replace_start_point((grid, sp)))
+        .and_then(|(grid, sp)| {
+            // walk it once now so nothing after this has to worry about a broken loop
+            loop_tiles(&grid, sp)?;
+            Ok((grid, sp))
+        });
 
-            grid.push(this_line);
+    checked.map_err(|e| match e.at() {
+        None => not_found("starting point 'S'"),
+        Some((x, y)) => ParseError::Invalid {
+            line_number: row_lines[y],
+            column: x,
+            problem: e.to_string(),
+        },
+    })
+}
 
-            (grid, new_sp_opt.unwrap_or(sp))
-        }),
-    )
+/// any grid of pipes, whether or not it has a start or a loop through it. if there's an 'S'
+/// that only one pipe could fit, it's filled in, otherwise it's left out of every loop
+pub fn parse_network(data: &str) -> Result<Grid<char>, ParseError> {
+    let (grid, _) = parse_tiles(data)?;
+    Ok(match start_point(&grid) {
+        Some(sp) => replace_start_point((grid.clone(), sp)).map_or(grid, |(grid, _)| grid),
+        None => grid,
+    })
 }
 
 type Step = u64;
 
+/// what `parse_grid` already made sure of
+const LOOP_CHECKED: &str = "the loop was checked when parsing";
+
 /**
  * calls the callback with each non-starting-point coordinate and how many steps it is
  * from the starting point
  */
-fn traverse_loop<F>(grid: &Grid, start_point: Coord, init_dir: Cardinal, mut callback: F)
+fn traverse_loop<F>(
+    grid: &Grid<char>,
+    start_point: Coord,
+    init_dir: Dir,
+    mut callback: F,
+) -> Result<(), PipeError>
 where
     F: FnMut(Coord, Step) -> bool,
 {
+    // the next tile in `dir`, as long as its pipe connects back to this one
+    let step = |from: Coord, dir: Dir| {
+        let to = grid
+            .step(from, dir)
+            .ok_or(PipeError::LeavesGrid { at: from })?;
+        if connects_to(grid[to], dir.opposite()) {
+            Ok(to)
+        } else {
+            Err(PipeError::Broken { at: to })
+        }
+    };
+
     let mut curr_dir = init_dir;
-    let mut curr_coord = neighbor(start_point, init_dir).unwrap();
+    let mut curr_coord = step(start_point, init_dir)?;
     let mut steps = 1;
 
     loop {
@@ -165,99 +210,326 @@ where
             break;
         }
 
-        let this_pipe = char_at(grid, curr_coord).unwrap();
-        let (dirs_a, dirs_b) = connects(this_pipe).unwrap();
+        let (dirs_a, dirs_b) =
+            connects(grid[curr_coord]).ok_or(PipeError::Broken { at: curr_coord })?;
 
-        curr_dir = if opposite(dirs_a) == curr_dir {
+        curr_dir = if dirs_a.opposite() == curr_dir {
             dirs_b
         } else {
             dirs_a
         };
-        curr_coord = neighbor(curr_coord, curr_dir).unwrap();
+        curr_coord = step(curr_coord, curr_dir)?;
         steps += 1;
     }
+
+    Ok(())
 }
 
-fn doit(data: &GridAndStartPoint) -> u64 {
-    let (grid, sp) = data;
-    let sp = *sp;
+/// every tile on the loop, starting with the start point
+fn loop_tiles(grid: &Grid<char>, sp: Coord) -> Result<Vec<Coord>, PipeError> {
+    let mut tiles = vec![sp];
+    let (dir, _) = connects(grid[sp]).ok_or(PipeError::NoPipe { at: sp })?;
+    traverse_loop(grid, sp, dir, |c, _| {
+        tiles.push(c);
+        false
+    })?;
+    Ok(tiles)
+}
+
+/// how many tiles a loop goes around, from the order of its tiles
+fn enclosed_count(tiles: &[Coord]) -> i64 {
+    let num_of_boundary_points = tiles.len();
+
+    // definitely had to scroll through aoc reddit to find somebody mentioning shoelace formula
+    // to find the area of a polygon, and pick's theorem to find the area given number of
+    // internal points & number of boundary points (to rearrange and solve for interior, given
+    // we find the area first)
+
+    let area = ((0..num_of_boundary_points).fold(0i64, |acc, i| {
+        let (x1, y1) = tiles[i];
+        let (x2, y2) = tiles[(i + 1) % num_of_boundary_points];
+
+        acc + (x1 as i64 * y2 as i64) - (y1 as i64 * x2 as i64)
+    }) / 2)
+        .abs();
+
+    area + 1 - (num_of_boundary_points as i64 / 2)
+}
+
+/// one closed loop of pipes
+#[derive(Debug, PartialEq)]
+pub struct PipeLoop {
+    /// every tile on the loop, in order, starting from wherever we started walking
+    pub tiles: Vec<Coord>,
+    /// the tile furthest from the start, going either way round
+    pub farthest: Coord,
+    /// how many tiles the loop goes around
+    pub enclosed: usize,
+}
 
-    let line_len = grid[0].len();
-    let line_count = grid.len();
+impl PipeLoop {
+    fn new(tiles: Vec<Coord>) -> PipeLoop {
+        // a loop on a grid always has an even number of tiles, so halfway round is the one
+        // tile that's just as far both ways
+        PipeLoop {
+            farthest: tiles[tiles.len() / 2],
+            enclosed: enclosed_count(&tiles) as usize,
+            tiles,
+        }
+    }
+
+    /// how many steps it takes to get from the start to [PipeLoop::farthest]
+    pub fn steps(&self) -> usize {
+        self.tiles.len() / 2
+    }
+}
 
-    let mut distance_grid: Vec<_> = (0..line_count).map(|_| vec![0; line_len]).collect();
+/// the loop through `start`, which can be any tile on it
+pub fn loop_from(grid: &Grid<char>, start: Coord) -> Result<PipeLoop, PipeError> {
+    if !grid.in_bounds(start) {
+        return Err(PipeError::NoPipe { at: start });
+    }
+    loop_tiles(grid, start).map(PipeLoop::new)
+}
 
-    let (dir_a, dir_b) = connects(char_at(grid, sp).unwrap()).unwrap();
+/// every closed loop in the grid, in the order their first tiles come reading row by row, each
+/// starting from that first tile
+pub fn all_loops(grid: &Grid<char>) -> Vec<PipeLoop> {
+    let mut seen = Grid::new(grid.width(), grid.height(), false);
+    let mut loops = vec![];
 
-    let mut fill_in_steps = |init_dir: Cardinal| {
-        traverse_loop(grid, sp, init_dir, |curr_coord, which_step| {
-            let (x, y) = curr_coord;
+    grid.positions().for_each(|pos| {
+        let Some((dir, _)) = connects(grid[pos]).filter(|_| !seen[pos]) else {
+            return;
+        };
 
-            let curr_value_in_grid = distance_grid[y][x];
-            if curr_value_in_grid == 0 || which_step < curr_value_in_grid {
-                distance_grid[y][x] = which_step;
-                false
+        let mut tiles = vec![pos];
+        let closed = traverse_loop(grid, pos, dir, |c, _| {
+            tiles.push(c);
+            false
+        })
+        .is_ok();
+
+        // a tile on a loop only ever leads to other tiles on it, so if this walk didn't come
+        // back round, nothing it went through is on one either
+        tiles.iter().for_each(|&c| seen[c] = true);
+        if closed {
+            loops.push(PipeLoop::new(tiles));
+        }
+    });
+    loops
+}
+
+/// which tiles the loop goes around. walking along a row, every loop tile with a pipe going
+/// north crosses from outside to inside or back
+fn enclosed(grid: &Grid<char>, on_loop: &Grid<bool>) -> Grid<bool> {
+    let mut inside = Grid::new(grid.width(), grid.height(), false);
+    (0..grid.height()).for_each(|y| {
+        let mut crossings = 0;
+        (0..grid.width()).for_each(|x| {
+            if on_loop[(x, y)] {
+                if connects_to(grid[(x, y)], North) {
+                    crossings += 1;
+                }
             } else {
-                true
+                inside[(x, y)] = crossings % 2 == 1;
             }
         });
+    });
+    inside
+}
+
+fn loop_mask(grid: &Grid<char>, sp: Coord) -> Grid<bool> {
+    let mut on_loop = Grid::new(grid.width(), grid.height(), false);
+    loop_tiles(grid, sp)
+        .expect(LOOP_CHECKED)
+        .into_iter()
+        .for_each(|c| on_loop[c] = true);
+    on_loop
+}
+
+/// every tile the loop encloses. should always be as many as [doit2] counts
+pub fn interior(data: &GridAndStartPoint) -> HashSet<Coord> {
+    let (grid, sp) = data;
+    let inside = enclosed(grid, &loop_mask(grid, *sp));
+
+    let tiles: HashSet<_> = inside
+        .iter()
+        .filter(|&(_, &i)| i)
+        .map(|(pos, _)| pos)
+        .collect();
+    debug_assert_eq!(tiles.len() as i64, doit2(data));
+    tiles
+}
+
+fn box_drawing(c: char) -> char {
+    match c {
+        '|' => '│',
+        '-' => '─',
+        'L' => '└',
+        'J' => '┘',
+        '7' => '┐',
+        'F' => '┌',
+        c => c,
+    }
+}
+
+/// redraws the loop with box drawing characters, leaving out any pipes that aren't part of
+/// it, and marks the tiles it encloses with 'I'
+pub fn render(data: &GridAndStartPoint) -> String {
+    let (grid, sp) = data;
+
+    let on_loop = loop_mask(grid, *sp);
+    let inside = interior(data);
+
+    let mut out = String::new();
+    (0..grid.height()).for_each(|y| {
+        (0..grid.width()).for_each(|x| {
+            out.push(match (on_loop[(x, y)], inside.contains(&(x, y))) {
+                (true, _) => box_drawing(grid[(x, y)]),
+                (_, true) => 'I',
+                _ => ' ',
+            })
+        });
+        out.push('\n');
+    });
+    out
+}
+
+pub type DistanceMap = Grid<Option<Step>>;
+
+/// how many steps each tile on the loop is from the start, going whichever way round is
+/// shorter. tiles that aren't on the loop are `None`
+pub fn distances(data: &GridAndStartPoint) -> DistanceMap {
+    let (grid, sp) = data;
+    let sp = *sp;
+
+    let mut distance_grid = Grid::new(grid.width(), grid.height(), None);
+    distance_grid[sp] = Some(0);
+
+    let (dir_a, dir_b) = connects(grid[sp]).expect(LOOP_CHECKED);
+
+    let mut fill_in_steps = |init_dir: Dir| {
+        traverse_loop(
+            grid,
+            sp,
+            init_dir,
+            |curr_coord, which_step| match distance_grid[curr_coord] {
+                Some(d) if d <= which_step => true,
+                _ => {
+                    distance_grid[curr_coord] = Some(which_step);
+                    false
+                }
+            },
+        )
+        .expect(LOOP_CHECKED);
     };
 
     fill_in_steps(dir_a);
 
     fill_in_steps(dir_b);
 
-    distance_grid.into_iter().flatten().max().unwrap()
+    distance_grid
 }
 
-fn doit2(data: &GridAndStartPoint) -> i64 {
-    let (grid, sp) = data;
-    let sp = *sp;
+fn max_distance(distances: &DistanceMap) -> Step {
+    distances.iter().filter_map(|(_, &d)| d).max().unwrap_or(0)
+}
 
-    let mut edge_coords = vec![sp];
+/// the tiles as far from the start as it gets
+pub fn farthest(distances: &DistanceMap) -> HashSet<Coord> {
+    let max = max_distance(distances);
+    distances
+        .iter()
+        .filter(|&(_, &d)| d == Some(max))
+        .map(|(pos, _)| pos)
+        .collect()
+}
 
-    let (dir, _) = connects(char_at(grid, sp).unwrap()).unwrap();
-    traverse_loop(grid, sp, dir, |c, _| {
-        edge_coords.push(c);
+/// one digit per loop tile, from 0 near the start up to 9 for the furthest tenth of the way
+/// round, and '.' everywhere else
+pub fn heatmap_text(distances: &DistanceMap) -> String {
+    let max = max_distance(distances);
+
+    let mut out = String::new();
+    distances.rows().for_each(|row| {
+        row.iter().for_each(|d| {
+            out.push(match d {
+                Some(d) => char::from_digit((d * 10 / (max + 1)) as u32, 10).unwrap(),
+                None => '.',
+            })
+        });
+        out.push('\n');
+    });
+    out
+}
 
-        false
+/// a binary ppm image with a pixel per tile, fading from blue at the start to red at the
+/// furthest point, and black off the loop
+pub fn heatmap_ppm(distances: &DistanceMap) -> Vec<u8> {
+    let max = max_distance(distances).max(1);
+
+    let mut out = format!("P6\n{} {}\n255\n", distances.width(), distances.height()).into_bytes();
+    distances.iter().for_each(|(_, d)| {
+        let pixel = match d {
+            Some(d) => {
+                let red = (d * 255 / max) as u8;
+                [red, 0, 255 - red]
+            }
+            None => [0, 0, 0],
+        };
+        out.extend_from_slice(&pixel);
     });
+    out
+}
 
-    let num_of_boundary_points = edge_coords.len();
+fn doit(data: &GridAndStartPoint) -> u64 {
+    max_distance(&distances(data))
+}
 
-    edge_coords.push(sp);
+fn doit2(data: &GridAndStartPoint) -> i64 {
+    let (grid, sp) = data;
+    enclosed_count(&loop_tiles(grid, *sp).expect(LOOP_CHECKED))
+}
 
-    // definitely had to scroll through aoc reddit to find somebody mentioning shoelace formula
-    // to find the area of a polygon, and pick's theorem to find the area given number of
-    // internal points & number of boundary points (to rearrange and solve for interior, given
-    // we find the area first)
+pub struct Day10;
 
-    let area = ((0..num_of_boundary_points).fold(0i64, |acc, i| {
-        let (x1, y1) = edge_coords[i];
-        let (x2, y2) = edge_coords[i + 1];
+impl Solution for Day10 {
+    const DAY: u32 = 10;
 
-        acc + (x1 as i64 * y2 as i64) - (y1 as i64 * x2 as i64)
-    }) / 2)
-        .abs();
+    type Input<'a> = GridAndStartPoint;
+    type Answer1 = u64;
+    type Answer2 = i64;
 
-    area + 1 - (num_of_boundary_points as i64 / 2)
+    fn parse(data: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_grid(data)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> u64 {
+        doit(input)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> i64 {
+        doit2(input)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::util::read_file_panic;
+    use crate::verify::recorded_answer;
 
     use super::*;
 
     #[test]
     fn t1() {
         let data = &read_file_panic("./data/day10/part1/test1.txt");
-        let data = &parse_grid(data);
+        let data = &parse_grid(data).unwrap();
         let answer = doit(data);
         assert_eq!(answer, 4);
 
         let data = &read_file_panic("./data/day10/part1/test2.txt");
-        let data = &parse_grid(data);
+        let data = &parse_grid(data).unwrap();
         let answer = doit(data);
         assert_eq!(answer, 8);
     }
@@ -265,29 +537,179 @@ mod tests {
     #[test]
     fn t2() {
         let data = &read_file_panic("./data/day10/part2/test1.txt");
-        let data = &parse_grid(data);
+        let data = &parse_grid(data).unwrap();
         let answer = doit2(data);
         assert_eq!(answer, 4);
 
         let data = &read_file_panic("./data/day10/part2/test2.txt");
-        let data = &parse_grid(data);
+        let data = &parse_grid(data).unwrap();
         let answer = doit2(data);
         assert_eq!(answer, 8);
 
         let data = &read_file_panic("./data/day10/part2/test3.txt");
-        let data = &parse_grid(data);
+        let data = &parse_grid(data).unwrap();
         let answer = doit2(data);
         assert_eq!(answer, 10);
     }
 
+    #[test]
+    fn bad_input() {
+        let err = |data: &str| parse_grid(data).unwrap_err().to_string();
+
+        assert_eq!(
+            err(".....\n.F-7.\n.|.|.\n.L-J.\n"),
+            "there's no starting point 'S' in the input"
+        );
+        assert_eq!(
+            err("\n.....\n.S-7.\n.|.|.\n.L-|.\n"),
+            "Line 5, column 4: the loop breaks at 3,3"
+        );
+        assert_eq!(
+            err(".|...\n-S-7.\n.|.|.\n.L-J.\n"),
+            "Line 2, column 2: the start at 1,1 connects to 4 pipes instead of 2"
+        );
+        assert_eq!(
+            err("S-7\n|.|\n|..\n"),
+            "Line 3, column 1: the loop leaves the grid at 0,2"
+        );
+        assert_eq!(
+            err(".S.\n...\n"),
+            "Line 1, column 2: the start at 1,0 connects to 0 pipes instead of 2"
+        );
+    }
+
+    #[test]
+    fn t_interior() {
+        let data = &read_file_panic("./data/day10/part2/test1.txt");
+        let data = &parse_grid(data).unwrap();
+        let mut tiles = interior(data).into_iter().collect::<Vec<_>>();
+        tiles.sort_by_key(|&(x, y)| (y, x));
+        assert_eq!(tiles, vec![(2, 6), (3, 6), (7, 6), (8, 6)]);
+
+        [
+            "part1/test1",
+            "part1/test2",
+            "part2/test2",
+            "part2/test3",
+            "data",
+        ]
+        .into_iter()
+        .for_each(|name| {
+            let data = &read_file_panic(&format!("./data/day10/{name}.txt"));
+            let data = &parse_grid(data).unwrap();
+            assert_eq!(interior(data).len() as i64, doit2(data), "{name}");
+        });
+    }
+
+    #[test]
+    fn t_render() {
+        let data = &read_file_panic("./data/day10/part2/test1.txt");
+        let data = &parse_grid(data).unwrap();
+        assert_eq!(
+            render(data),
+            [
+                "           ",
+                " ┌───────┐ ",
+                " │┌─────┐│ ",
+                " ││     ││ ",
+                " ││     ││ ",
+                " │└─┐ ┌─┘│ ",
+                " │II│ │II│ ",
+                " └──┘ └──┘ ",
+                "           ",
+            ]
+            .map(|line| format!("{line}\n"))
+            .concat()
+        );
+
+        let data = &read_file_panic("./data/day10/part2/test3.txt");
+        let data = &parse_grid(data).unwrap();
+        assert_eq!(render(data).matches('I').count(), 10);
+
+        // junk pipes that aren't on the loop get blanked out, or marked if they're enclosed
+        let data = &parse_grid("-L|F7\n7S-7|\nL|7||\n-L-J|\nL|-JF\n").unwrap();
+        assert_eq!(render(data), "     \n ┌─┐ \n │I│ \n └─┘ \n     \n");
+    }
+
+    #[test]
+    fn t_loops() {
+        let grid = &parse_network("F-7F--7\n|.|L-7|\nL-J..LJ\n.F7....\n.LJ.-|.\n").unwrap();
+        let summary = all_loops(grid)
+            .iter()
+            .map(|l| (l.tiles[0], l.tiles.len(), l.farthest, l.enclosed))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![
+                ((0, 0), 8, (2, 2), 1),
+                ((3, 0), 10, (6, 2), 0),
+                ((1, 3), 4, (2, 4), 0)
+            ]
+        );
+
+        let l = loop_from(grid, (5, 1)).unwrap();
+        // right next door, but the pipes only meet going the long way round
+        assert_eq!((l.tiles.len(), l.steps(), l.farthest), (10, 5, (5, 0)));
+        assert_eq!(
+            loop_from(grid, (1, 1)),
+            Err(PipeError::NoPipe { at: (1, 1) })
+        );
+        assert_eq!(
+            loop_from(grid, (7, 0)),
+            Err(PipeError::NoPipe { at: (7, 0) })
+        );
+        assert_eq!(
+            loop_from(grid, (4, 4)),
+            Err(PipeError::Broken { at: (5, 4) })
+        );
+
+        // the puzzle's loop is in there, wherever we start on it
+        let data = &read_file_panic("./data/day10/data.txt");
+        let (grid, sp) = &parse_grid(data).unwrap();
+        let network = &parse_network(data).unwrap();
+        let main = all_loops(network)
+            .into_iter()
+            .find(|l| l.tiles.contains(sp))
+            .unwrap();
+        assert_eq!((main.steps(), main.enclosed), (6907, 541));
+        let l = loop_from(grid, main.farthest).unwrap();
+        assert_eq!((l.steps(), l.enclosed), (6907, 541));
+    }
+
+    #[test]
+    fn t_distances() {
+        let data = &read_file_panic("./data/day10/part1/test1.txt");
+        let data = &parse_grid(data).unwrap();
+        let map = distances(data);
+        assert_eq!(farthest(&map), HashSet::from([(3, 3)]));
+        assert_eq!(heatmap_text(&map), ".....\n.024.\n.2.6.\n.468.\n.....\n");
+
+        let ppm = heatmap_ppm(&map);
+        let header = "P6\n5 5\n255\n";
+        assert!(ppm.starts_with(header.as_bytes()));
+        assert_eq!(ppm.len(), header.len() + 5 * 5 * 3);
+        // the start is blue, the furthest tile red
+        let pixel = |(x, y): Coord| &ppm[header.len() + (y * 5 + x) * 3..][..3];
+        assert_eq!(pixel((1, 1)), [0, 0, 255]);
+        assert_eq!(pixel((3, 3)), [255, 0, 0]);
+        assert_eq!(pixel((0, 0)), [0, 0, 0]);
+
+        let data = &read_file_panic("./data/day10/data.txt");
+        let data = &parse_grid(data).unwrap();
+        let map = distances(data);
+        let far = farthest(&map);
+        assert_eq!(far.len(), 1);
+        assert!(far.iter().all(|&c| map[c] == Some(doit(data))));
+    }
+
     #[test]
     fn d() {
         let data = &read_file_panic("./data/day10/data.txt");
-        let data = &parse_grid(data);
+        let data = &parse_grid(data).unwrap();
         let answer = doit(data);
-        assert_eq!(answer, 6907);
+        assert_eq!(answer.to_string(), recorded_answer(10, 1));
 
         let answer = doit2(data);
-        assert_eq!(answer, 541);
+        assert_eq!(answer.to_string(), recorded_answer(10, 2));
     }
 }
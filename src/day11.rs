@@ -1,43 +1,48 @@
 use std::collections::HashSet;
 
+use crate::error::ParseError;
+use crate::solution::Solution;
 use crate::util::get_non_empty_lines;
 
 type XPos = i64;
 type YPos = i64;
 type Coord = (XPos, YPos);
-struct PictureData {
+pub struct PictureData {
     galaxy_coords: Vec<Coord>,
     cols_with_no_galaxies: HashSet<usize>,
     rows_with_no_galaxies: HashSet<usize>,
 }
 
-fn get_picture_data(data: &str) -> PictureData {
+fn get_picture_data(data: &str) -> Result<PictureData, ParseError> {
     let mut galaxy_coords = Vec::new();
     let mut cols_with_no_galaxies = HashSet::new();
     let mut rows_with_no_galaxies = HashSet::new();
 
-    get_non_empty_lines(data).for_each(|line| {
+    // blank lines don't count as rows, so `line_number` is just for errors
+    for (row, line) in get_non_empty_lines(data).enumerate() {
         let mut has_galaxy = false;
-        line.line.chars().enumerate().for_each(|(col, char)| {
+        for (col, char) in line.line.char_indices() {
             if char == '#' {
                 has_galaxy = true;
                 cols_with_no_galaxies.remove(&col);
-                galaxy_coords.push((col as i64, line.line_number as i64));
-            } else if line.line_number == 0 {
+                galaxy_coords.push((col as i64, row as i64));
+            } else if char != '.' {
+                return Err(line.unexpected(line.char_at(col), "'#' or '.'"));
+            } else if row == 0 {
                 // on the first line, put all the columns with empty space so we can remove them later
                 cols_with_no_galaxies.insert(col);
             }
-        });
+        }
         if !has_galaxy {
-            rows_with_no_galaxies.insert(line.line_number);
+            rows_with_no_galaxies.insert(row);
         }
-    });
+    }
 
-    PictureData {
+    Ok(PictureData {
         galaxy_coords,
         cols_with_no_galaxies,
         rows_with_no_galaxies,
-    }
+    })
 }
 
 fn expand_universe(picture: &PictureData, expansion_factor: u32) -> Vec<Coord> {
@@ -90,16 +95,39 @@ fn doit2(data: &PictureData) -> i64 {
     doit_impl(data, 1_000_000)
 }
 
+pub struct Day11;
+
+impl Solution for Day11 {
+    const DAY: u32 = 11;
+
+    type Input<'a> = PictureData;
+    type Answer1 = i64;
+    type Answer2 = i64;
+
+    fn parse(data: &str) -> Result<Self::Input<'_>, ParseError> {
+        get_picture_data(data)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> i64 {
+        doit(input)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> i64 {
+        doit2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::util::read_file_panic;
+    use crate::verify::recorded_answer;
 
     use super::*;
 
     #[test]
     fn t1() {
         let data = &read_file_panic("./data/day11/test.txt");
-        let data = &get_picture_data(data);
+        let data = &get_picture_data(data).unwrap();
         let answer = doit(data);
         assert_eq!(answer, 374);
     }
@@ -107,7 +135,7 @@ mod tests {
     #[test]
     fn t2() {
         let data = &read_file_panic("./data/day11/test.txt");
-        let data = &get_picture_data(data);
+        let data = &get_picture_data(data).unwrap();
         let answer = doit_impl(data, 10);
         assert_eq!(answer, 1030);
 
@@ -115,14 +143,27 @@ mod tests {
         assert_eq!(answer, 8410);
     }
 
+    #[test]
+    fn t_blank_lines() {
+        let data = &read_file_panic("./data/day11/test.txt");
+        let data = &get_picture_data(&format!("\n\n{data}")).unwrap();
+        assert_eq!(doit(data), 374);
+
+        let err = get_picture_data("\n..#\n.x.\n").err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            r#"Line 3, column 2: expected '#' or '.', found "x""#
+        );
+    }
+
     #[test]
     fn d() {
         let data = &read_file_panic("./data/day11/data.txt");
-        let data = &get_picture_data(data);
+        let data = &get_picture_data(data).unwrap();
         let answer = doit(data);
-        assert_eq!(answer, 9274989);
+        assert_eq!(answer.to_string(), recorded_answer(11, 1));
 
         let answer = doit2(data);
-        assert_eq!(answer, 357134560737);
+        assert_eq!(answer.to_string(), recorded_answer(11, 2));
     }
 }
@@ -1,141 +1,575 @@
-use lazy_static::lazy_static;
-use regex::Regex;
-use std::collections::HashMap;
-
-use crate::util::{get_non_empty_lines, DataLine};
-
-lazy_static! {
-    static ref NUM_RE: Regex = Regex::new(r"\d+").unwrap();
-}
+use crate::error::ParseError;
+use crate::solution::Solution;
+use crate::util::{get_non_empty_lines, par_map, BigUint, DataLine};
 
 #[derive(Debug)]
-struct ParsedLine {
+pub struct ParsedLine {
     first_part: String,
     nums: Vec<usize>,
 }
 
-fn parse_line(line: DataLine) -> ParsedLine {
-    let (first, last) = line.line.split_once(' ').unwrap();
+fn parse_line(line: DataLine) -> Result<ParsedLine, ParseError> {
+    let (first, last) = line
+        .line
+        .split_once(' ')
+        .ok_or_else(|| line.unexpected(line.line, "<springs> <groups>"))?;
 
-    let nums = NUM_RE
-        .find_iter(last)
-        .map(|m| m.as_str().parse().unwrap())
-        .collect();
+    if let Some(idx) = first.find(|c| !".#?".contains(c)) {
+        return Err(line.unexpected(line.char_at(line.column_of(first) + idx), "'.', '#' or '?'"));
+    }
 
-    ParsedLine {
+    // every field has to be a number, so a stray or missing one isn't quietly skipped
+    let nums = last
+        .split(',')
+        .map(|num| line.parse_num(num))
+        .collect::<Result<_, _>>()?;
+
+    Ok(ParsedLine {
         first_part: String::from(first),
         nums,
+    })
+}
+
+pub fn parse_lines(data: &str) -> Result<Vec<ParsedLine>, ParseError> {
+    get_non_empty_lines(data).map(parse_line).collect()
+}
+
+/// what the arrangement counting can be done in. `plus` is `None` on overflow
+trait Count: Clone {
+    fn zero() -> Self;
+    fn one() -> Self;
+    fn plus(&self, other: &Self) -> Option<Self>;
+}
+
+impl Count for u64 {
+    fn zero() -> Self {
+        0
+    }
+    fn one() -> Self {
+        1
+    }
+    fn plus(&self, other: &Self) -> Option<Self> {
+        self.checked_add(*other)
     }
 }
 
-fn count_valid_solutions(line: ParsedLine) -> u64 {
-    fn rec(
-        rem_str: &[char],
-        rem_nums: &[usize],
-        memo: &mut HashMap<(String, Vec<usize>), u64>,
-    ) -> u64 {
-        if let Some(a) = memo.get(&(String::from_iter(rem_str.iter()), rem_nums.to_vec())) {
-            return *a;
-        }
+impl Count for BigUint {
+    fn zero() -> Self {
+        BigUint::default()
+    }
+    fn one() -> Self {
+        BigUint::from(1)
+    }
+    fn plus(&self, other: &Self) -> Option<Self> {
+        Some(self + other)
+    }
+}
+
+/// `ways[i * (groups.len() + 1) + g]` is how many ways the unknown springs in springs[i..] can
+/// be filled in so the damaged ones form exactly groups[g..], in order. `None` if any of those
+/// don't fit in `N`
+fn count_table<N: Count>(springs: &[u8], groups: &[usize]) -> Option<Vec<N>> {
+    let (n, width) = (springs.len(), groups.len() + 1);
+
+    // there's an extra row past the end, for a group that finishes right at the end of the
+    // springs
+    let mut ways = vec![N::zero(); (n + 2) * width];
+    ways[n * width + groups.len()] = N::one();
+    ways[(n + 1) * width + groups.len()] = N::one();
+
+    // the fewest springs groups[..g] can fit in, and the fewest groups[g..] can. only the
+    // cells between those limits can be reached from the start, so the rest stay zero
+    let before = |g: usize| groups[..g].iter().sum::<usize>() + g;
+    let after = |g: usize| {
+        let rest = &groups[g..];
+        rest.iter().sum::<usize>() + rest.len().saturating_sub(1)
+    };
+    let (before, after): (Vec<_>, Vec<_>) = (0..width).map(|g| (before(g), after(g))).unzip();
 
-        if (rem_str.is_empty() || rem_str.iter().all(|&c| c != '#')) && rem_nums.is_empty() {
-            1
-        } else if rem_str.is_empty() || rem_nums.is_empty() {
+    // how many springs in a row starting at i could be damaged
+    let mut damaged_run = 0;
+
+    for i in (0..n).rev() {
+        damaged_run = if springs[i] == b'.' {
             0
         } else {
-            let curr_char = rem_str[0];
-            if curr_char == '.' {
-                rec(&rem_str[1..], rem_nums, memo)
-            } else if curr_char == '?' {
-                let mut a = rem_str.to_vec();
-                a[0] = '.';
-                let a = rec(&a, rem_nums, memo);
-
-                let mut b = rem_str.to_vec();
-                b[0] = '#';
-                let b = rec(&b, rem_nums, memo);
-
-                memo.insert(
-                    (String::from_iter(rem_str.iter()), rem_nums.to_vec()),
-                    a + b,
-                );
-                a + b
+            damaged_run + 1
+        };
+
+        // row i + 1 onwards is already filled in, and row i + k starts at later[(k - 1) * width]
+        let (here, later) = ways.split_at_mut((i + 1) * width);
+        let here = &mut here[i * width..];
+
+        for g in (0..width).filter(|&g| before[g] <= i && after[g] <= n - i) {
+            // leave this spring operational
+            let skip = (springs[i] != b'#').then(|| &later[g]);
+
+            // or start the next group here, as long as it isn't followed right away by
+            // another damaged spring
+            let place = match groups.get(g) {
+                Some(&len) if damaged_run >= len && springs.get(i + len) != Some(&b'#') => {
+                    Some(&later[len * width + g + 1])
+                }
+                _ => None,
+            };
+
+            here[g] = match (skip, place) {
+                (Some(a), Some(b)) => a.plus(b)?,
+                (Some(a), None) | (None, Some(a)) => a.clone(),
+                (None, None) => N::zero(),
+            };
+        }
+    }
+
+    Some(ways)
+}
+
+/// how many ways the whole row can be filled in. `None` if that doesn't fit in `N`
+fn count_arrangements<N: Count>(springs: &[u8], groups: &[usize]) -> Option<N> {
+    Some(count_table(springs, groups)?.swap_remove(0))
+}
+
+/// could a group of `len` damaged springs start at `i`
+fn group_fits(springs: &[u8], i: usize, len: usize) -> bool {
+    i + len <= springs.len()
+        && springs[i..i + len].iter().all(|&c| c != b'.')
+        && springs.get(i + len) != Some(&b'#')
+}
+
+/// every way of filling in a row, in order with '.' before '#'. skipping ahead (with `nth`
+/// or `skip`) walks the count table instead of the arrangements in between, so any page of
+/// them is quick to get to
+pub struct Arrangements<'a> {
+    springs: &'a [u8],
+    groups: &'a [usize],
+    ways: Vec<u64>,
+    next: u64,
+}
+
+impl Arrangements<'_> {
+    pub fn total(&self) -> u64 {
+        self.ways[0]
+    }
+
+    /// the arrangement with `rank` arrangements before it
+    fn unrank(&self, mut rank: u64) -> String {
+        let (n, width) = (self.springs.len(), self.groups.len() + 1);
+        let mut out = String::with_capacity(n);
+        let (mut i, mut g) = (0, 0);
+
+        while i < n {
+            let skip = if self.springs[i] != b'#' {
+                self.ways[(i + 1) * width + g]
             } else {
-                // char is #, let's see if the first number can go here
-                let this_num = rem_nums[0];
-
-                let can_fit = (0..this_num).all(|i| rem_str.get(i).is_some_and(|&c| c != '.'))
-                    && !rem_str.get(this_num).is_some_and(|&c| c == '#');
-
-                if can_fit {
-                    // we are saying this one can fit, which means that the new start can only be
-                    // '.' if it was a wildcard before
-                    let mut next = rem_str[this_num..].to_vec();
-                    if !next.is_empty() && next[0] == '?' {
-                        next[0] = '.';
-                    }
-                    rec(&next, &rem_nums[1..], memo)
-                } else {
-                    memo.insert((String::from_iter(rem_str.iter()), rem_nums.to_vec()), 0);
-                    0
+                0
+            };
+
+            if rank < skip {
+                out.push('.');
+                i += 1;
+            } else {
+                rank -= skip;
+                let len = self.groups[g];
+                debug_assert!(group_fits(self.springs, i, len));
+                (0..len).for_each(|_| out.push('#'));
+                if i + len < n {
+                    out.push('.');
                 }
+                i += len + 1;
+                g += 1;
             }
         }
+
+        out
+    }
+}
+
+impl Iterator for Arrangements<'_> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        (self.next < self.total()).then(|| {
+            self.next += 1;
+            self.unrank(self.next - 1)
+        })
+    }
+
+    fn nth(&mut self, n: usize) -> Option<String> {
+        self.next = self.next.saturating_add(n as u64).min(self.total());
+        self.next()
     }
 
-    let str: Vec<_> = line.first_part.chars().collect();
-    let mut memo = HashMap::new();
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let left = usize::try_from(self.total() - self.next).ok();
+        (left.unwrap_or(usize::MAX), left)
+    }
+}
 
-    rec(&str, &line.nums, &mut memo)
+/// `None` if there are more than a u64 can count
+pub fn arrangements(line: &ParsedLine) -> Option<Arrangements<'_>> {
+    let (springs, groups) = (line.first_part.as_bytes(), &line.nums[..]);
+    Some(Arrangements {
+        springs,
+        groups,
+        ways: count_table(springs, groups)?,
+        next: 0,
+    })
 }
 
-fn doit(data: &str) -> u64 {
-    get_non_empty_lines(data)
-        .map(parse_line)
-        .map(count_valid_solutions)
-        .sum()
+/// `copies` of the springs joined by `separator`, with the groups repeated to match
+fn unfold(line: &ParsedLine, copies: usize, separator: char) -> ParsedLine {
+    ParsedLine {
+        first_part: vec![line.first_part.as_str(); copies].join(&separator.to_string()),
+        nums: line.nums.repeat(copies),
+    }
 }
 
-fn doit2(data: &str) -> u64 {
-    get_non_empty_lines(data)
-        .map(parse_line)
-        .map(|pl| {
-            let first_part = vec![pl.first_part; 5].join("?");
-            let nums = vec![pl.nums; 5].into_iter().flatten().collect();
+/// counts in a u64 when that's big enough, since it's a lot quicker
+fn count_big(line: &ParsedLine) -> BigUint {
+    let (springs, groups) = (line.first_part.as_bytes(), &line.nums[..]);
+    count_arrangements::<u64>(springs, groups)
+        .map(BigUint::from)
+        .or_else(|| count_arrangements(springs, groups))
+        .unwrap()
+}
 
-            ParsedLine { first_part, nums }
-        })
-        .map(count_valid_solutions)
+/// the total arrangements once every line is unfolded. `separator` should be one of '.', '#'
+/// or '?'
+pub fn unfolded_total(lines: &[ParsedLine], copies: usize, separator: char) -> BigUint {
+    par_map(lines, |pl| count_big(&unfold(pl, copies, separator)))
+        .into_iter()
         .sum()
 }
 
+fn doit(lines: &[ParsedLine]) -> BigUint {
+    par_map(lines, count_big).into_iter().sum()
+}
+
+fn doit2(lines: &[ParsedLine]) -> BigUint {
+    unfolded_total(lines, 5, '?')
+}
+
+pub struct Day12;
+
+impl Solution for Day12 {
+    const DAY: u32 = 12;
+
+    type Input<'a> = Vec<ParsedLine>;
+    type Answer1 = BigUint;
+    type Answer2 = BigUint;
+
+    fn parse(data: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_lines(data)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> BigUint {
+        doit(input)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> BigUint {
+        doit2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::util::{print_dur, read_file_panic};
+    use crate::verify::recorded_answer;
 
     use super::*;
 
     #[test]
     fn t1() {
         let data = &read_file_panic("./data/day12/test.txt");
+        let data = &parse_lines(data).unwrap();
         let answer = print_dur("test1", || doit(data));
-        assert_eq!(answer, 21);
+        assert_eq!(answer, BigUint::from(21));
+
+        // even without unfolding, a row can have more arrangements than fit in a u64
+        let row = format!("{} {}\n", "?".repeat(120), vec!["1"; 25].join(","));
+        let answer = doit(&parse_lines(&row).unwrap());
+        assert_eq!(answer.to_string(), "75172948856947447223328");
     }
 
     #[test]
     fn t2() {
         let data = &read_file_panic("./data/day12/test.txt");
+        let data = &parse_lines(data).unwrap();
         let answer = print_dur("test2", || doit2(data));
-        assert_eq!(answer, 525152);
+        assert_eq!(answer.to_u64(), Some(525152));
+    }
+
+    #[test]
+    fn t_count() {
+        assert_eq!(count_arrangements(b"???.###", &[1, 1, 3]), Some(1u64));
+        assert_eq!(count_arrangements(b"?###????????", &[3, 2, 1]), Some(10u64));
+        assert_eq!(count_arrangements(b"", &[]), Some(1u64));
+        assert_eq!(count_arrangements(b"", &[1]), Some(0u64));
+        assert_eq!(count_arrangements(b"#", &[]), Some(0u64));
+        assert_eq!(count_arrangements(b"??", &[2]), Some(1u64));
+        assert_eq!(count_arrangements(b"##", &[1]), Some(0u64));
+    }
+
+    #[test]
+    fn t_arrangements() {
+        let lines = &parse_lines("???.### 1,1,3\n.??..??...?##. 1,1,3\n#.# 2\n").unwrap();
+
+        let all = arrangements(&lines[0]).unwrap().collect::<Vec<_>>();
+        assert_eq!(all, vec!["#.#.###"]);
+
+        let all = arrangements(&lines[1]).unwrap().collect::<Vec<_>>();
+        assert_eq!(
+            all,
+            vec![
+                "..#...#...###.",
+                "..#..#....###.",
+                ".#....#...###.",
+                ".#...#....###.",
+            ]
+        );
+
+        assert_eq!(arrangements(&lines[2]).unwrap().next(), None);
+
+        let line = &unfold(&parse_lines("?###???????? 3,2,1\n").unwrap()[0], 5, '?');
+        let mut iter = arrangements(line).unwrap();
+        assert_eq!(iter.total(), 506250);
+        assert_eq!(iter.size_hint(), (506250, Some(506250)));
+
+        // paging straight to the end should agree with walking there one at a time
+        let last_page = iter.by_ref().skip(506248).collect::<Vec<_>>();
+        assert_eq!(last_page.len(), 2);
+        assert_eq!(
+            arrangements(line).unwrap().last().as_ref(),
+            last_page.last()
+        );
+        assert_eq!(iter.next(), None);
+    }
+
+    /// xorshift, so failures are reproducible from the seed
+    struct Rng(u64);
+
+    impl Rng {
+        fn below(&mut self, n: usize) -> usize {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            (self.0 % n as u64) as usize
+        }
+    }
+
+    fn groups_of(springs: &str) -> Vec<usize> {
+        springs
+            .split('.')
+            .filter(|g| !g.is_empty())
+            .map(|g| g.len())
+            .collect()
+    }
+
+    /// a row with at least one arrangement: picks the real springs first, takes the groups
+    /// from those, then forgets some of them
+    fn random_row(rng: &mut Rng) -> ParsedLine {
+        let len = 1 + rng.below(14);
+        let real = (0..len)
+            .map(|_| if rng.below(2) == 0 { '.' } else { '#' })
+            .collect::<String>();
+        let nums = groups_of(&real);
+        let first_part = real
+            .chars()
+            .map(|c| if rng.below(3) == 0 { c } else { '?' })
+            .collect();
+        ParsedLine { first_part, nums }
+    }
+
+    fn brute_force(line: &ParsedLine) -> u64 {
+        let unknown = line.first_part.matches('?').count();
+        (0..1u32 << unknown)
+            .filter(|bits| {
+                let mut bit = 0;
+                let filled = line
+                    .first_part
+                    .chars()
+                    .map(|c| match c {
+                        '?' => {
+                            bit += 1;
+                            if bits & 1 << (bit - 1) == 0 {
+                                '.'
+                            } else {
+                                '#'
+                            }
+                        }
+                        c => c,
+                    })
+                    .collect::<String>();
+                groups_of(&filled) == line.nums
+            })
+            .count() as u64
+    }
+
+    /// everything we know about a row that should agree with brute force
+    fn check(line: &ParsedLine) -> Result<(), String> {
+        let expected = brute_force(line);
+        let counted = count_big(line).to_u64().unwrap();
+        let listed = arrangements(line).unwrap().collect::<Vec<_>>();
+
+        let bad = listed.iter().find(|a| {
+            groups_of(a) != line.nums
+                || a.len() != line.first_part.len()
+                || a.chars()
+                    .zip(line.first_part.chars())
+                    .any(|(a, p)| p != '?' && p != a)
+        });
+
+        if counted != expected || listed.len() as u64 != expected {
+            Err(format!(
+                "brute force found {expected}, counted {counted}, listed {}",
+                listed.len()
+            ))
+        } else if let Some(bad) = bad {
+            Err(format!("listed {bad}, which doesn't fit"))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// smaller versions of a row: a spring or group taken out, a '?' filled in, or a group
+    /// made shorter
+    fn shrink(line: &ParsedLine) -> Vec<ParsedLine> {
+        let springs = &line.first_part;
+        let nums = &line.nums;
+        let mut out = vec![];
+
+        (0..springs.len()).for_each(|i| {
+            let mut fewer = springs.clone();
+            fewer.remove(i);
+            out.push(ParsedLine {
+                first_part: fewer,
+                nums: nums.clone(),
+            });
+
+            if springs.as_bytes()[i] == b'?' {
+                ['.', '#'].into_iter().for_each(|c| {
+                    let mut filled = springs.clone();
+                    filled.replace_range(i..i + 1, &c.to_string());
+                    out.push(ParsedLine {
+                        first_part: filled,
+                        nums: nums.clone(),
+                    });
+                });
+            }
+        });
+
+        (0..nums.len()).for_each(|g| {
+            let mut fewer = nums.clone();
+            if fewer[g] > 1 {
+                fewer[g] -= 1;
+            } else {
+                fewer.remove(g);
+            }
+            out.push(ParsedLine {
+                first_part: springs.clone(),
+                nums: fewer,
+            });
+        });
+
+        out
+    }
+
+    /// keeps taking the first smaller row that still fails `check`, until none of them do
+    fn minimize<F>(mut line: ParsedLine, mut error: String, check: F) -> (ParsedLine, String)
+    where
+        F: Fn(&ParsedLine) -> Result<(), String>,
+    {
+        while let Some((smaller, e)) = shrink(&line)
+            .into_iter()
+            .find_map(|l| check(&l).err().map(|e| (l, e)))
+        {
+            line = smaller;
+            error = e;
+        }
+        (line, error)
+    }
+
+    #[test]
+    fn t_props() {
+        let mut rng = Rng(0x2023_1212);
+        (0..2000).for_each(|_| {
+            let line = random_row(&mut rng);
+            if let Err(e) = check(&line) {
+                let (line, e) = minimize(line, e, check);
+                panic!("{} {:?}: {e}", line.first_part, line.nums);
+            }
+        });
+    }
+
+    #[test]
+    fn t_shrink() {
+        // pretend the dp can't cope with a damaged spring at the start
+        let broken = |l: &ParsedLine| {
+            if l.first_part.starts_with('#') {
+                Err(String::from("oops"))
+            } else {
+                Ok(())
+            }
+        };
+        let line = ParsedLine {
+            first_part: String::from("#?.??#"),
+            nums: vec![1, 1, 2],
+        };
+
+        let (line, _) = minimize(line, String::from("oops"), broken);
+        assert_eq!(line.first_part, "#");
+        assert!(line.nums.is_empty());
+    }
+
+    #[test]
+    fn t_unfold() {
+        let data = &read_file_panic("./data/day12/test.txt");
+        let data = &parse_lines(data).unwrap();
+
+        assert_eq!(unfolded_total(data, 1, '?'), doit(data));
+        assert_eq!(unfolded_total(data, 5, '?'), doit2(data));
+
+        // "?#?" can't be two groups of one, but "?.?" has to be
+        let line = &parse_lines("? 1\n").unwrap();
+        assert_eq!(unfolded_total(line, 2, '#').to_u64(), Some(0));
+        assert_eq!(unfolded_total(line, 2, '.').to_u64(), Some(1));
+
+        // copies kept apart by an operational spring multiply out
+        let line = &parse_lines("?###???????? 3,2,1\n").unwrap();
+        assert_eq!(unfolded_total(line, 2, '.').to_u64(), Some(100));
+
+        // 10^30 doesn't fit in a u64
+        let big = unfolded_total(line, 30, '.');
+        assert_eq!(big.to_u64(), None);
+        assert_eq!(big.to_string(), format!("1{}", "0".repeat(30)));
+    }
+
+    #[test]
+    fn bad_input() {
+        let err = |data: &str| parse_lines(data).unwrap_err().to_string();
+
+        assert_eq!(
+            err("???.### 1,x,3\n"),
+            r#"Line 1, column 11: expected a number, found "x""#
+        );
+        assert_eq!(
+            err("#.# 2\n???.### 1,,1,3\n"),
+            r#"Line 2, column 11: expected a number, found """#
+        );
+        assert_eq!(
+            err("???.### \n"),
+            r#"Line 1, column 9: expected a number, found """#
+        );
     }
 
     #[test]
     fn d() {
         let data = &read_file_panic("./data/day12/data.txt");
+        let data = &parse_lines(data).unwrap();
         let answer = print_dur("real pt1", || doit(data));
-        assert_eq!(answer, 7857);
+        assert_eq!(answer.to_string(), recorded_answer(12, 1));
 
         let answer = print_dur("real pt2", || doit2(data));
-        assert_eq!(answer, 28606137449920);
+        assert_eq!(answer.to_string(), recorded_answer(12, 2));
     }
 }
@@ -1,147 +1,268 @@
-use tailcall::tailcall;
+use std::mem;
 
-use crate::util::get_lines;
+use crate::error::ParseError;
+use crate::solution::Solution;
+use crate::util::{get_lines, Bits, DataLine, Grid, Pos};
 
-#[derive(Clone)]
-struct BlockInfo {
-    num_rows: usize,
-    num_cols: usize,
-}
-struct Block {
-    data: Vec<Vec<char>>,
-    info: BlockInfo,
+pub struct Block {
+    data: Grid<char>,
+    /// where the block starts in the input, for errors
+    line_number: usize,
 }
 struct NumBlock {
-    cols: Vec<u64>,
-    rows: Vec<u64>,
+    cols: Vec<Bits>,
+    rows: Vec<Bits>,
 }
-enum MirrorPoint {
+/// the mirror sits just after this row or column
+#[derive(Debug, PartialEq)]
+pub enum MirrorPoint {
     Column(usize),
     Row(usize),
 }
 
-impl MirrorPoint {
-    fn value_if(&self, is_col: bool) -> Option<usize> {
-        match self {
-            MirrorPoint::Column(c) => {
-                if is_col {
-                    Some(*c)
-                } else {
-                    None
-                }
-            }
-            MirrorPoint::Row(r) => {
-                if is_col {
-                    None
-                } else {
-                    Some(*r)
-                }
-            }
-        }
-    }
+/// a mirror at 45 degrees, which can only reflect a square part of the block. it starts at
+/// the top of the square, at this cell's corner
+#[derive(Debug, PartialEq)]
+pub enum DiagonalMirror {
+    /// running down and to the right from the cell's top left corner
+    Diagonal(Pos),
+    /// running down and to the left from the cell's top right corner
+    AntiDiagonal(Pos),
 }
 
-fn convert_to_number<'a>(chars: impl Iterator<Item = &'a char>) -> u64 {
-    #[tailcall]
-    fn rec<'a>(acc: u64, mult: u64, mut chars: impl Iterator<Item = &'a char>) -> u64 {
-        match chars.next() {
-            None => acc,
-            Some(&c) => {
-                let this_bit = if c == '.' { 0 } else { 1 };
-                rec(acc + this_bit * mult, 2 * mult, chars)
-            }
-        }
-    }
+#[derive(Debug, PartialEq)]
+pub struct Reflection<M = MirrorPoint> {
+    pub mirror: M,
+    /// how many rows or columns on each side actually get reflected, before one side runs
+    /// into the edge. for a diagonal mirror, how wide the square it reflects is
+    pub span: usize,
+}
 
-    rec(0, 1, chars)
+fn convert_to_number<'a>(chars: impl Iterator<Item = &'a char>) -> Bits {
+    chars.map(|&c| c == '#').collect()
 }
 
 fn block_to_numblock(block: &Block) -> NumBlock {
-    fn block_col(block: &Block, col: usize) -> impl Iterator<Item = &char> {
-        (0..block.data.len()).map(move |i| &block.data[i][col])
-    }
-
     let rows = block
         .data
-        .iter()
+        .rows()
         .map(|line| convert_to_number(line.iter()))
         .collect();
-    let cols = (0..block.data[0].len())
-        .map(|col| block_col(&block, col))
-        .map(convert_to_number)
-        .collect();
+    let cols = block.data.columns().map(convert_to_number).collect();
 
     NumBlock { rows, cols }
 }
 
-fn is_mirrorpoint(nums: &Vec<u64>, mirror_after: usize) -> bool {
-    #[tailcall]
-    fn rec(nums: &Vec<u64>, mirror_after: usize, delta: usize) -> bool {
-        let left = if (delta - 1) > mirror_after {
-            None
-        } else {
-            nums.get(mirror_after - (delta - 1))
-        };
-        let right = nums.get(mirror_after + delta);
-
-        match (left, right) {
-            (Some(l), Some(r)) => {
-                if l != r {
-                    false
-                } else {
-                    rec(nums, mirror_after, 1 + delta)
-                }
-            }
-            (_, _) => true,
-        }
-    }
+/// how many cells differ between the two sides of a mirror between `mirror_after` and the
+/// one after it
+fn mismatches(nums: &[Bits], mirror_after: usize) -> u32 {
+    let (before, after) = nums.split_at(mirror_after + 1);
+    before
+        .iter()
+        .rev()
+        .zip(after)
+        .map(|(l, r)| l.count_differences(r))
+        .sum()
+}
 
-    rec(nums, mirror_after, 1)
+/// every mirror that would be perfect if exactly `smudges` cells were flipped
+fn mirrorpoints(nums: &[Bits], smudges: u32) -> impl Iterator<Item = usize> + '_ {
+    (0..nums.len().saturating_sub(1)).filter(move |&mirror| mismatches(nums, mirror) == smudges)
 }
 
-fn block_to_smudge_corrected(block: &Block) -> Vec<Block> {
-    let mut ret = vec![];
+/// rows first, then columns
+fn numblock_to_reflections(block: &NumBlock, smudges: u32) -> Vec<Reflection> {
+    let reflection =
+        |len: usize, mirror_after: usize| (mirror_after + 1).min(len - mirror_after - 1);
 
-    (0..block.info.num_rows).for_each(|row| {
-        (0..block.info.num_cols).for_each(|col| {
-            let mut data = block.data.clone();
-            data[row][col] = if data[row][col] == '.' { '#' } else { '.' };
-            ret.push(Block {
-                data,
-                info: block.info.clone(),
-            })
+    let rows = mirrorpoints(&block.rows, smudges).map(|r| Reflection {
+        mirror: MirrorPoint::Row(r),
+        span: reflection(block.rows.len(), r),
+    });
+    let cols = mirrorpoints(&block.cols, smudges).map(|c| Reflection {
+        mirror: MirrorPoint::Column(c),
+        span: reflection(block.cols.len(), c),
+    });
+
+    rows.chain(cols).collect()
+}
+
+/// rows win if there's a mirror both ways
+fn numblock_to_mirrorpoint(block: &NumBlock, smudges: u32) -> Option<MirrorPoint> {
+    numblock_to_reflections(block, smudges)
+        .into_iter()
+        .next()
+        .map(|r| r.mirror)
+}
+
+/// the cells on the top or left side of the mirror that don't match their reflection
+fn smudges_for(block: &Block, mirror: &MirrorPoint) -> Vec<Pos> {
+    let data = &block.data;
+    // the position reflected across the mirror, if it's still on the block
+    let reflect = |(x, y): Pos| match *mirror {
+        MirrorPoint::Row(r) => (2 * r + 1).checked_sub(y).map(|y| (x, y)),
+        MirrorPoint::Column(c) => (2 * c + 1).checked_sub(x).map(|x| (x, y)),
+    };
+    let on_top_or_left = |(x, y): Pos| match *mirror {
+        MirrorPoint::Row(r) => y <= r,
+        MirrorPoint::Column(c) => x <= c,
+    };
+
+    data.positions()
+        .filter(|&pos| on_top_or_left(pos))
+        .filter(|&pos| {
+            reflect(pos).is_some_and(|other| data.get(other).is_some_and(|&c| c != data[pos]))
         })
+        .collect()
+}
+
+/// draws the block with the mirror marked like the puzzle does, `><` above and below a
+/// column mirror or `v^` either side of a row mirror, and the smudges highlighted (reversed
+/// with `ansi`, otherwise as '*')
+pub fn render(block: &Block, mirror: Option<&MirrorPoint>, ansi: bool) -> String {
+    let data = &block.data;
+    let smudges = mirror.map_or(vec![], |m| smudges_for(block, m));
+
+    let column_markers = match mirror {
+        Some(&MirrorPoint::Column(c)) => {
+            let line = (0..data.width())
+                .map(|x| match x {
+                    _ if x == c => '>',
+                    _ if x == c + 1 => '<',
+                    _ => ' ',
+                })
+                .collect::<String>();
+            Some(String::from(line.trim_end()))
+        }
+        _ => None,
+    };
+    let row_marker = |y: usize| match mirror {
+        Some(&MirrorPoint::Row(r)) if y == r => "v",
+        Some(&MirrorPoint::Row(r)) if y == r + 1 => "^",
+        Some(MirrorPoint::Row(_)) => " ",
+        _ => "",
+    };
+
+    let mut out = String::new();
+    if let Some(markers) = &column_markers {
+        out.push_str(markers);
+        out.push('\n');
+    }
+    data.rows().enumerate().for_each(|(y, row)| {
+        out.push_str(row_marker(y));
+        row.iter().enumerate().for_each(|(x, &c)| {
+            if !smudges.contains(&(x, y)) {
+                out.push(c);
+            } else if ansi {
+                out.push_str(&format!("\x1b[7m{c}\x1b[0m"));
+            } else {
+                out.push('*');
+            }
+        });
+        out.push_str(row_marker(y).trim_end());
+        out.push('\n');
     });
+    if let Some(markers) = &column_markers {
+        out.push_str(markers);
+        out.push('\n');
+    }
+    out
+}
 
-    ret
+impl DiagonalMirror {
+    /// the cells of the `span` wide square this mirror runs across
+    fn square(&self, span: usize) -> impl Iterator<Item = Pos> {
+        let (x0, y0) = match *self {
+            DiagonalMirror::Diagonal(pos) => pos,
+            DiagonalMirror::AntiDiagonal((x, y)) => (x + 1 - span, y),
+        };
+        (y0..y0 + span).flat_map(move |y| (x0..x0 + span).map(move |x| (x, y)))
+    }
+
+    /// where `pos`, somewhere in the square, ends up when it's reflected
+    fn reflect(&self, (x, y): Pos) -> Pos {
+        match *self {
+            DiagonalMirror::Diagonal((x0, y0)) => (x0 + (y - y0), y0 + (x - x0)),
+            DiagonalMirror::AntiDiagonal((x1, y0)) => (x1 - (y - y0), y0 + (x1 - x)),
+        }
+    }
 }
 
-fn find_mirrorpoint(nums: &Vec<u64>, but_not: Option<usize>) -> Option<usize> {
-    (0..nums.len() - 1)
-        .filter(|i| !but_not.iter().any(|j| j == i))
-        .find(|&mirror| is_mirrorpoint(nums, mirror))
+/// every square a 45 degree mirror could run corner to corner across, in a block this size
+fn diagonal_squares(width: usize, height: usize) -> Vec<Reflection<DiagonalMirror>> {
+    // one along each line x - y = k
+    let diagonals = (1 - height as isize..width as isize).map(|k| {
+        let (x0, y0) = (k.max(0) as usize, (-k).max(0) as usize);
+        Reflection {
+            mirror: DiagonalMirror::Diagonal((x0, y0)),
+            span: (width - x0).min(height - y0),
+        }
+    });
+    // and along each line x + y = k
+    let anti_diagonals = (0..width + height - 1).map(|k| {
+        let (x1, y0) = (k.min(width - 1), (k + 1).saturating_sub(width));
+        Reflection {
+            mirror: DiagonalMirror::AntiDiagonal((x1, y0)),
+            span: (x1 + 1).min(height - y0),
+        }
+    });
+    diagonals.chain(anti_diagonals).collect()
 }
 
-fn numblock_to_mirrorpoint_opt(
-    but_not: &Option<MirrorPoint>,
-    block: NumBlock,
-) -> Option<MirrorPoint> {
-    find_mirrorpoint(
-        &block.rows,
-        but_not.iter().flat_map(|o| o.value_if(false)).next(),
-    )
-    .map(MirrorPoint::Row)
-    .or_else(|| {
-        find_mirrorpoint(
-            &block.cols,
-            but_not.iter().flat_map(|o| o.value_if(true)).next(),
-        )
-        .map(MirrorPoint::Column)
-    })
+/// the symmetries of the whole block besides mirror lines
+#[derive(Debug, PartialEq)]
+pub enum Symmetry {
+    /// the same after turning it a quarter turn
+    QuarterTurn,
+    /// the same after turning it upside down
+    HalfTurn,
 }
 
-fn numblock_to_mirrorpoint(but_not: &Option<MirrorPoint>, block: NumBlock) -> MirrorPoint {
-    numblock_to_mirrorpoint_opt(but_not, block).unwrap()
+impl Block {
+    /// where the puzzle would put the mirror, with exactly `smudges` cells flipped
+    pub fn mirror(&self, smudges: u32) -> Option<MirrorPoint> {
+        numblock_to_mirrorpoint(&block_to_numblock(self), smudges)
+    }
+
+    /// every line the block reflects across with exactly `smudges` cells flipped, not just
+    /// the one the puzzle counts
+    pub fn reflections(&self, smudges: u32) -> Vec<Reflection> {
+        numblock_to_reflections(&block_to_numblock(self), smudges)
+    }
+
+    /// every square part of the block that reflects across a 45 degree line through its
+    /// corners, with exactly `smudges` cells flipped. a single cell doesn't count
+    pub fn diagonal_reflections(&self, smudges: u32) -> Vec<Reflection<DiagonalMirror>> {
+        let data = &self.data;
+        diagonal_squares(data.width(), data.height())
+            .into_iter()
+            .filter(|r| r.span > 1)
+            .filter(|r| {
+                // each cell that doesn't match gets seen from both sides of the mirror
+                let flipped = r
+                    .mirror
+                    .square(r.span)
+                    .filter(|&pos| data[pos] != data[r.mirror.reflect(pos)])
+                    .count();
+                flipped as u32 == smudges * 2
+            })
+            .collect()
+    }
+
+    /// a quarter turn only ever works for square blocks
+    pub fn symmetries(&self) -> Vec<Symmetry> {
+        let data = &self.data;
+        let half_turn = data.rotate_clockwise().rotate_clockwise();
+
+        [
+            (Symmetry::QuarterTurn, data.rotate_clockwise()),
+            (Symmetry::HalfTurn, half_turn),
+        ]
+        .into_iter()
+        .filter(|(_, other)| other == data)
+        .map(|(symmetry, _)| symmetry)
+        .collect()
+    }
 }
 
 fn mirrorpoint_to_num(mp: MirrorPoint) -> u64 {
@@ -151,77 +272,110 @@ fn mirrorpoint_to_num(mp: MirrorPoint) -> u64 {
     }
 }
 
-fn parse_blocks(data: &str) -> Vec<Block> {
-    let mut blocks = vec![];
-
-    fn block_from(v: Vec<Vec<char>>) -> Block {
-        let info = BlockInfo {
-            num_rows: v.len(),
-            num_cols: v[0].len(),
-        };
-        Block { data: v, info }
+pub fn parse_blocks(data: &str) -> Result<Vec<Block>, ParseError> {
+    fn block_from(lines: Vec<DataLine>) -> Result<Block, ParseError> {
+        let line_number = lines[0].line_number;
+        let data = Grid::parse_lines(lines, |line, col, c| match c {
+            '.' | '#' => Ok(c),
+            _ => Err(line.unexpected(line.char_at(col), "'.' or '#'")),
+        })?;
+        Ok(Block { data, line_number })
     }
 
-    let last_opt = get_lines(data).fold(None, |acc, line| {
+    let mut blocks = vec![];
+    let mut curr_block = vec![];
+
+    for line in get_lines(data) {
         if line.line.trim().is_empty() {
-            if let Some(prev) = acc {
-                blocks.push(block_from(prev));
+            if !curr_block.is_empty() {
+                blocks.push(block_from(mem::take(&mut curr_block))?);
             }
-            None
         } else {
-            let this_line = line.line.chars().collect();
-            match acc {
-                None => Some(vec![this_line]),
-                Some(mut curr_block) => {
-                    curr_block.push(this_line);
-                    Some(curr_block)
-                }
-            }
+            curr_block.push(line);
         }
-    });
+    }
 
-    if let Some(last) = last_opt {
-        blocks.push(block_from(last))
+    if !curr_block.is_empty() {
+        blocks.push(block_from(curr_block)?)
     }
 
-    blocks
+    Ok(blocks)
+}
+
+/// the puzzle only makes sense if every block has a mirror for this many smudges
+fn check_mirrors(blocks: &[Block], smudges: u32) -> Result<(), ParseError> {
+    match blocks.iter().find(|b| b.mirror(smudges).is_none()) {
+        Some(block) => Err(ParseError::Invalid {
+            line_number: block.line_number,
+            column: 0,
+            problem: format!("there's no mirror in this block for smudge count {smudges}"),
+        }),
+        None => Ok(()),
+    }
 }
 
-fn doit(data: &str) -> u64 {
-    parse_blocks(data)
+/// what `check_mirrors` already made sure of
+const MIRRORS_CHECKED: &str = "every block's mirror was checked first";
+
+fn doit_impl(blocks: &[Block], smudges: u32) -> u64 {
+    blocks
         .iter()
         .map(block_to_numblock)
-        .map(|b| numblock_to_mirrorpoint(&None, b))
+        .map(|b| numblock_to_mirrorpoint(&b, smudges).expect(MIRRORS_CHECKED))
         .map(mirrorpoint_to_num)
         .sum()
 }
 
-fn doit2(data: &str) -> u64 {
-    parse_blocks(data)
-        .iter()
-        .map(|block| {
-            let old_mirror_point = &numblock_to_mirrorpoint_opt(&None, block_to_numblock(block));
-
-            block_to_smudge_corrected(block)
-                .iter()
-                .map(block_to_numblock)
-                .filter_map(|b| numblock_to_mirrorpoint_opt(old_mirror_point, b))
-                .next()
-                .unwrap()
-        })
-        .map(mirrorpoint_to_num)
-        .sum()
+fn doit(blocks: &[Block]) -> u64 {
+    doit_impl(blocks, 0)
+}
+
+fn doit2(blocks: &[Block]) -> u64 {
+    doit_impl(blocks, 1)
+}
+
+pub struct Day13;
+
+impl Solution for Day13 {
+    const DAY: u32 = 13;
+
+    type Input<'a> = Vec<Block>;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(data: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_blocks(data)
+    }
+
+    fn check1(input: &Self::Input<'_>) -> Result<(), ParseError> {
+        check_mirrors(input, 0)
+    }
+
+    fn check2(input: &Self::Input<'_>) -> Result<(), ParseError> {
+        check_mirrors(input, 1)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> u64 {
+        doit(input)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> u64 {
+        doit2(input)
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::solution::Runner;
     use crate::util::{print_dur, read_file_panic};
+    use crate::verify::recorded_answer;
 
     use super::*;
 
     #[test]
     fn t1() {
         let data = &read_file_panic("./data/day13/test.txt");
+        let data = &parse_blocks(data).unwrap();
         let answer = doit(data);
         assert_eq!(answer, 405);
     }
@@ -229,17 +383,244 @@ mod tests {
     #[test]
     fn t2() {
         let data = &read_file_panic("./data/day13/test.txt");
+        let data = &parse_blocks(data).unwrap();
         let answer = doit2(data);
         assert_eq!(answer, 400);
     }
 
+    #[test]
+    fn t_smudges() {
+        let data = "#....\n.#...\n.#...\n##...\n";
+        let block = &block_to_numblock(&parse_blocks(data).unwrap()[0]);
+
+        assert_eq!(mismatches(&block.rows, 0), 2);
+        assert_eq!(mismatches(&block.rows, 1), 1);
+        assert_eq!(mismatches(&block.rows, 2), 1);
+
+        // the last two columns are empty, so they mirror perfectly
+        assert_eq!(
+            numblock_to_mirrorpoint(block, 0),
+            Some(MirrorPoint::Column(3))
+        );
+        assert_eq!(numblock_to_mirrorpoint(block, 1), Some(MirrorPoint::Row(1)));
+        assert_eq!(numblock_to_mirrorpoint(block, 2), Some(MirrorPoint::Row(0)));
+        assert_eq!(
+            numblock_to_mirrorpoint(block, 3),
+            Some(MirrorPoint::Column(0))
+        );
+        assert_eq!(numblock_to_mirrorpoint(block, 4), None);
+    }
+
+    #[test]
+    fn t_reflections() {
+        // mirrors both ways, across the whole block
+        let block = &parse_blocks("#..#\n#..#\n").unwrap()[0];
+        assert_eq!(
+            block.reflections(0),
+            vec![
+                Reflection {
+                    mirror: MirrorPoint::Row(0),
+                    span: 1
+                },
+                Reflection {
+                    mirror: MirrorPoint::Column(1),
+                    span: 2
+                },
+            ]
+        );
+
+        let block = &parse_blocks("##..#\n").unwrap()[0];
+        assert_eq!(
+            block.reflections(0),
+            vec![
+                Reflection {
+                    mirror: MirrorPoint::Column(0),
+                    span: 1
+                },
+                Reflection {
+                    mirror: MirrorPoint::Column(2),
+                    span: 2
+                },
+            ]
+        );
+        assert_eq!(
+            block.reflections(1),
+            vec![Reflection {
+                mirror: MirrorPoint::Column(3),
+                span: 1
+            }]
+        );
+    }
+
+    #[test]
+    fn t_symmetries() {
+        let symmetries = |data: &str| parse_blocks(data).unwrap()[0].symmetries();
+
+        assert_eq!(symmetries("#..\n.#.\n..#\n"), vec![Symmetry::HalfTurn]);
+        assert_eq!(
+            symmetries("#.#\n...\n#.#\n"),
+            vec![Symmetry::QuarterTurn, Symmetry::HalfTurn]
+        );
+        assert_eq!(symmetries(".#.#\n#.#.\n"), vec![Symmetry::HalfTurn]);
+        assert_eq!(symmetries("##.\n#..\n...\n"), vec![]);
+        assert_eq!(symmetries(".##\n#..\n"), vec![]);
+        // a pinwheel turns a quarter but doesn't mirror at all
+        assert_eq!(
+            symmetries(".#..\n...#\n#...\n..#.\n"),
+            vec![Symmetry::QuarterTurn, Symmetry::HalfTurn]
+        );
+    }
+
+    #[test]
+    fn t_diagonals() {
+        use DiagonalMirror::{AntiDiagonal, Diagonal};
+
+        let diagonals = |data: &str, smudges| {
+            parse_blocks(data).unwrap()[0]
+                .diagonal_reflections(smudges)
+                .into_iter()
+                .map(|r| (r.mirror, r.span))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            diagonals("#..\n.#.\n..#\n", 0),
+            vec![
+                (Diagonal((0, 0)), 3),
+                (AntiDiagonal((1, 0)), 2),
+                (AntiDiagonal((2, 0)), 3),
+                (AntiDiagonal((2, 1)), 2)
+            ]
+        );
+        assert!(diagonals("##.\n#..\n...\n", 0).contains(&(Diagonal((0, 0)), 3)));
+        assert!(diagonals(".##\n..#\n...\n", 0).contains(&(AntiDiagonal((2, 0)), 3)));
+
+        // blocks that aren't square still have square parts that reflect
+        let block = "#..##\n.#.#.\n..#..\n";
+        assert_eq!(
+            diagonals(block, 0),
+            vec![
+                (Diagonal((0, 0)), 3),
+                (Diagonal((3, 0)), 2),
+                (AntiDiagonal((1, 0)), 2),
+                (AntiDiagonal((2, 0)), 3)
+            ]
+        );
+        assert!(diagonals(block, 1).contains(&(AntiDiagonal((3, 0)), 3)));
+        assert!(!diagonals(block, 1).contains(&(Diagonal((0, 0)), 3)));
+    }
+
+    #[test]
+    fn t_render() {
+        let data = &read_file_panic("./data/day13/test.txt");
+        let blocks = &parse_blocks(data).unwrap();
+
+        let first = &blocks[0];
+        assert_eq!(
+            render(first, first.mirror(0).as_ref(), false),
+            "    ><\n\
+             #.##..##.\n\
+             ..#.##.#.\n\
+             ##......#\n\
+             ##......#\n\
+             ..#.##.#.\n\
+             ..##..##.\n\
+             #.#.##.#.\n\
+             \x20   ><\n"
+        );
+
+        // with the smudge between the first two rows, they're the mirror instead of rows 4
+        // and 5
+        let second = &blocks[1];
+        assert_eq!(
+            render(second, second.mirror(1).as_ref(), false),
+            "v#...*#..#v\n\
+             ^#....#..#^\n \
+             ..##..###\n \
+             #####.##.\n \
+             #####.##.\n \
+             ..##..###\n \
+             #....#..#\n"
+        );
+        assert!(render(second, second.mirror(1).as_ref(), true).contains("\x1b[7m#\x1b[0m"));
+        assert_eq!(render(second, None, false), second.data.to_string());
+    }
+
+    #[test]
+    fn t_wide() {
+        // 130 columns, each the binary for its column number, except the last 30 mirror the
+        // 30 before them. so no two columns next to each other match except 99 and 100
+        let cell = |row: usize, col: usize| {
+            let n = if col < 100 { col } else { 199 - col };
+            if n >> row & 1 == 1 {
+                '#'
+            } else {
+                '.'
+            }
+        };
+        let data = (0..7)
+            .map(|row| (0..130).map(|col| cell(row, col)).collect::<String>() + "\n")
+            .collect::<String>();
+
+        let block = &parse_blocks(&data).unwrap()[0];
+        let nums = &block_to_numblock(block);
+        assert_eq!(nums.cols.len(), 130);
+        assert_eq!(mirrorpoints(&nums.cols, 0).next(), Some(99));
+
+        // and the same thing on its side, 130 rows tall
+        let tall = &Block {
+            data: block.data.transpose(),
+            line_number: 0,
+        };
+        assert_eq!(
+            numblock_to_mirrorpoint(&block_to_numblock(tall), 0),
+            Some(MirrorPoint::Row(99))
+        );
+
+        // a smudge out past where the mirror reaches doesn't matter to it, but one inside
+        // does
+        let mut smudged = block.data.clone();
+        smudged[(0, 3)] = '#';
+        let nums = &block_to_numblock(&Block {
+            data: smudged.clone(),
+            line_number: 0,
+        });
+        assert_eq!(mirrorpoints(&nums.cols, 0).next(), Some(99));
+
+        smudged[(120, 3)] = if smudged[(120, 3)] == '#' { '.' } else { '#' };
+        let nums = &block_to_numblock(&Block {
+            data: smudged,
+            line_number: 0,
+        });
+        assert_eq!(mirrorpoints(&nums.cols, 0).next(), None);
+        assert_eq!(mismatches(&nums.cols, 99), 1);
+    }
+
+    #[test]
+    fn no_mirror() {
+        let data = "##\n##\n\n\n#.\n.#\n";
+        let err = Day13.run(data, 1).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Line 5, column 1: there's no mirror in this block for smudge count 0"
+        );
+
+        // the first block is a perfect mirror, so it's the one that can't take a smudge
+        let err = Day13.run(data, 2).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Line 1, column 1: there's no mirror in this block for smudge count 1"
+        );
+    }
+
     #[test]
     fn d() {
         let data = &read_file_panic("./data/day13/data.txt");
+        let data = &parse_blocks(data).unwrap();
         let answer = print_dur("part1", || doit(data));
-        assert_eq!(answer, 29213);
+        assert_eq!(answer.to_string(), recorded_answer(13, 1));
 
         let answer = print_dur("part2", || doit2(data));
-        assert_eq!(answer, 37453);
+        assert_eq!(answer.to_string(), recorded_answer(13, 2));
     }
 }
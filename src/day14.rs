@@ -1,164 +1,192 @@
-use crate::util::{get_non_empty_lines, DataLine};
-use regex::Regex;
-use std::collections::HashMap;
-
-fn slide_north(board: &mut Vec<Vec<char>>) {
-    (0..board[0].len()).for_each(|col| {
-        let mut min_row = 0;
-        loop {
-            match (min_row..board.len()).find(|&r| board[r][col] == 'O') {
-                None => break,
-                Some(curr_o_row) => {
-                    if curr_o_row == 0 || board[curr_o_row - 1][col] != '.' {
-                        min_row = curr_o_row + 1;
-                    } else {
-                        board[curr_o_row - 1][col] = 'O';
-                        board[curr_o_row][col] = '.';
-                    }
-                }
-            }
-        }
-    })
-}
-fn slide_west(board: &mut Vec<Vec<char>>) {
-    (0..board.len()).for_each(|row| {
-        let mut min_col = 0;
-        loop {
-            match (min_col..board[0].len()).find(|&c| board[row][c] == 'O') {
-                None => break,
-                Some(curr_o_col) => {
-                    if curr_o_col == 0 || board[row][curr_o_col - 1] != '.' {
-                        min_col = curr_o_col + 1
-                    } else {
-                        board[row][curr_o_col - 1] = 'O';
-                        board[row][curr_o_col] = '.';
-                    }
-                }
-            }
-        }
-    });
+use crate::error::ParseError;
+use crate::solution::Solution;
+use crate::util::Dir::{East, North, South, West};
+use crate::util::{find_cycle, get_non_empty_lines, nth_state, Cycle, DataLine, Dir, Grid, Pos};
+
+pub type Board = Grid<char>;
+
+/// the cells of one row or column, starting from the wall that `dir` tilts towards
+fn lane(board: &Board, dir: Dir, i: usize) -> Vec<Pos> {
+    let (w, h) = (board.width(), board.height());
+    match dir {
+        North => (0..h).map(|y| (i, y)).collect(),
+        South => (0..h).rev().map(|y| (i, y)).collect(),
+        West => (0..w).map(|x| (x, i)).collect(),
+        East => (0..w).rev().map(|x| (x, i)).collect(),
+    }
 }
-fn slide_south(board: &mut Vec<Vec<char>>) {
-    let board_row_count = board.len();
-    let last_row_idx = board_row_count - 1;
-    (0..board[0].len()).for_each(|col| {
-        let mut max_row = board_row_count - 1;
-        loop {
-            match (0..max_row).rev().find(|&r| board[r][col] == 'O') {
-                None => break,
-                Some(curr_o_row) => {
-                    if curr_o_row == last_row_idx || board[curr_o_row + 1][col] != '.' {
-                        max_row -= 1;
-                    } else {
-                        board[curr_o_row + 1][col] = 'O';
-                        board[curr_o_row][col] = '.';
-                    }
+
+/// rolls every round rock as far towards `dir` as it'll go
+fn tilt(board: &mut Board, dir: Dir) {
+    let lanes = match dir {
+        North | South => board.width(),
+        East | West => board.height(),
+    };
+
+    (0..lanes).for_each(|i| {
+        let lane = lane(board, dir, i);
+        // where the next rock we find in this lane will come to rest
+        let mut free = 0;
+        lane.iter()
+            .enumerate()
+            .for_each(|(k, &pos)| match board[pos] {
+                '#' => free = k + 1,
+                'O' => {
+                    board.swap(lane[free], pos);
+                    free += 1;
                 }
-            }
-        }
+                _ => {}
+            });
     })
 }
-fn slide_east(board: &mut Vec<Vec<char>>) {
-    let board_col_count = board[0].len();
-    let last_col_idx = board_col_count - 1;
-    (0..board.len()).for_each(|row| {
-        let mut max_col = board_col_count - 1;
-        loop {
-            match (0..max_col).rev().find(|&c| board[row][c] == 'O') {
-                None => break,
-                Some(curr_o_col) => {
-                    if curr_o_col == last_col_idx || board[row][curr_o_col + 1] != '.' {
-                        max_col -= 1;
-                    } else {
-                        board[row][curr_o_col + 1] = 'O';
-                        board[row][curr_o_col] = '.';
-                    }
-                }
-            }
-        }
-    })
+
+const SPIN_CYCLE: [Dir; 4] = [North, West, South, East];
+
+fn run_cycle(board: &Board) -> Board {
+    let mut out_board = board.clone();
+    SPIN_CYCLE.iter().for_each(|&dir| tilt(&mut out_board, dir));
+    out_board
 }
 
-fn run_cycle<'a>(
-    board: &'a Vec<Vec<char>>,
-    mut memo: HashMap<&'a Vec<Vec<char>>, Vec<Vec<char>>>,
-) -> (Vec<Vec<char>>, HashMap<&'a Vec<Vec<char>>, Vec<Vec<char>>>) {
-    match memo.get(board) {
-        Some(b) => (b.clone(), memo),
-        None => {
-            let mut out_board = board.clone();
-            slide_north(&mut out_board);
-            slide_west(&mut out_board);
-            slide_south(&mut out_board);
-            slide_east(&mut out_board);
-            let map_val = out_board.clone();
-            memo.insert(board, map_val);
-            (out_board, memo)
-        }
-    }
+/// a tilt sequence like "NWSE"
+pub fn parse_tilts(sequence: &str) -> Result<Vec<Dir>, ParseError> {
+    let line = DataLine {
+        line: sequence,
+        line_number: 0,
+    };
+    sequence
+        .char_indices()
+        .map(|(col, c)| match c {
+            'N' => Ok(North),
+            'S' => Ok(South),
+            'E' => Ok(East),
+            'W' => Ok(West),
+            _ => Err(line.unexpected(line.char_at(col), "N, S, E or W")),
+        })
+        .collect()
 }
 
-fn get_load(board: Vec<Vec<char>>) -> u64 {
-    let num_rows = board.len();
+/// tilts the board through `tilts`, `count` times over, giving the direction and the board
+/// before and after each single tilt as it goes
+pub fn run_tilts<'a>(
+    board: &Board,
+    tilts: &'a [Dir],
+    count: usize,
+) -> impl Iterator<Item = (Dir, Board, Board)> + 'a {
+    let mut board = board.clone();
+    (0..count).flat_map(move |_| tilts).map(move |&dir| {
+        let before = board.clone();
+        tilt(&mut board, dir);
+        (dir, before, board.clone())
+    })
+}
+
+/// each round rock weighs on the `side` beam by how far it is from the opposite edge
+pub fn get_load(board: &Board, side: Dir) -> u64 {
+    let (w, h) = (board.width(), board.height());
     board
-        .into_iter()
-        .enumerate()
-        .map(|(row_num, line)| {
-            ((num_rows - row_num) as u64) * line.into_iter().filter(|&c| c == 'O').count() as u64
-        })
+        .iter()
+        .filter(|&(_, &c)| c == 'O')
+        .map(|((x, y), _)| match side {
+            North => h - y,
+            South => y + 1,
+            West => w - x,
+            East => x + 1,
+        } as u64)
         .sum()
 }
 
-fn doit(data: &str) -> u64 {
-    let mut board = get_non_empty_lines(data)
-        .map(|dl| dl.line.chars().collect::<Vec<_>>())
-        .collect::<Vec<_>>();
+/// the load on `side` before any spin cycles, then after each of `cycles` of them, along
+/// with the loop the boards fall into
+pub fn load_history(board: &Board, side: Dir, cycles: usize) -> (Vec<u64>, Cycle<Board>) {
+    let cycle = find_cycle(board.clone(), run_cycle);
+    let loads = (0..=cycles).map(|n| get_load(cycle.nth(n), side)).collect();
+    (loads, cycle)
+}
+
+pub fn parse_board(data: &str) -> Result<Board, ParseError> {
+    Grid::parse_lines(get_non_empty_lines(data), |line, col, c| match c {
+        'O' | '#' | '.' => Ok(c),
+        _ => Err(line.unexpected(line.char_at(col), "'O', '#' or '.'")),
+    })
+}
 
-    slide_north(&mut board);
+fn doit(board: &Board) -> u64 {
+    let mut board = board.clone();
 
-    get_load(board)
+    tilt(&mut board, North);
+
+    get_load(&board, North)
 }
 
-fn print_board(desc: &str, board: &Vec<Vec<char>>) {
-    println!("{desc}:");
-    board.iter().for_each(|l| {
-        println!(
-            "{}",
-            l.iter()
-                .map(|&c| format!("{c}"))
-                .collect::<Vec<_>>()
-                .join("")
-        )
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Highlight {
+    /// just the board
+    Plain,
+    /// rocks that moved in are green, the spots they left are red
+    Ansi,
+    /// rocks that moved in are '@', the spots they left are '~'
+    Marked,
+}
+
+/// draws `after`, highlighting the round rocks that moved since `before`
+pub fn render(before: &Board, after: &Board, highlight: Highlight) -> String {
+    let mut out = String::new();
+    after.rows().enumerate().for_each(|(y, row)| {
+        row.iter().enumerate().for_each(|(x, &c)| {
+            let was = before.get((x, y)).copied().unwrap_or(c);
+            match (highlight, was, c) {
+                (Highlight::Ansi, '.', 'O') => out.push_str("\x1b[1;32mO\x1b[0m"),
+                (Highlight::Ansi, 'O', '.') => out.push_str("\x1b[31m.\x1b[0m"),
+                (Highlight::Marked, '.', 'O') => out.push('@'),
+                (Highlight::Marked, 'O', '.') => out.push('~'),
+                _ => out.push(c),
+            }
+        });
+        out.push('\n');
     });
-    println!("==========");
+    out
 }
 
-fn doit2(data: &str) -> u64 {
-    // need to find when it loops to an older one, find out the number between loops,
-    // shorten the number of cycles by modulo that, then find the answer. because this
-    // dumb version doesn't finish
+const SPIN_CYCLES: usize = 1000000000;
 
-    let mut board = get_non_empty_lines(data)
-        .map(|dl| dl.line.chars().collect::<Vec<_>>())
-        .collect::<Vec<_>>();
+fn doit2(board: &Board) -> u64 {
+    // the boards settle into a loop long before a billion cycles, so skip ahead once it does
+    get_load(&nth_state(board.clone(), run_cycle, SPIN_CYCLES), North)
+}
 
-    let mut memo = HashMap::new();
-    let (board, _) = (0..1000000000).fold((board.clone(), memo), |(old_board, memo), _| {
-        run_cycle(&board, memo)
-    });
-    // (0..1000000000).for_each(|_| run_cycle(&board));
+pub struct Day14;
+
+impl Solution for Day14 {
+    const DAY: u32 = 14;
+
+    type Input<'a> = Board;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(data: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_board(data)
+    }
 
-    get_load(board)
+    fn part1(input: &Self::Input<'_>) -> u64 {
+        doit(input)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> u64 {
+        doit2(input)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::util::read_file_panic;
+    use crate::verify::recorded_answer;
 
     #[test]
     fn t1() {
         let data = &read_file_panic("./data/day14/test.txt");
+        let data = &parse_board(data).unwrap();
         let answer = doit(data);
         assert_eq!(answer, 136);
     }
@@ -166,17 +194,80 @@ mod tests {
     #[test]
     fn t2() {
         let data = &read_file_panic("./data/day14/test.txt");
+        let data = &parse_board(data).unwrap();
         let answer = doit2(data);
         assert_eq!(answer, 64);
     }
 
+    #[test]
+    fn t_tilts() {
+        let data = &read_file_panic("./data/day14/test.txt");
+        let board = &parse_board(data).unwrap();
+
+        let north_loads = |tilts: &[Dir], count| {
+            run_tilts(board, tilts, count)
+                .map(|(_, _, after)| get_load(&after, North))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(north_loads(&[North], 1), vec![136]);
+
+        // a thousand spin cycles, one tilt at a time, should agree with skipping ahead
+        let loads = north_loads(&parse_tilts("NWSE").unwrap(), 1000);
+        assert_eq!(loads.len(), 4000);
+        assert_eq!(loads[3], get_load(&run_cycle(board), North));
+        assert_eq!(
+            loads[3999],
+            get_load(&nth_state(board.clone(), run_cycle, 1000), North)
+        );
+
+        // tilting the same way twice doesn't move anything the second time
+        let loads = north_loads(&parse_tilts("SS").unwrap(), 1);
+        assert_eq!(loads[0], loads[1]);
+
+        assert!(parse_tilts("NWX").is_err());
+    }
+
+    #[test]
+    fn t_render() {
+        let before = &parse_board("O.#\n..O\n").unwrap();
+        let (dir, tilted, after) = &run_tilts(before, &[South], 1).next().unwrap();
+        assert_eq!((dir, tilted), (&South, before));
+
+        assert_eq!(render(before, after, Highlight::Plain), "..#\nO.O\n");
+        assert_eq!(render(before, after, Highlight::Marked), "~.#\n@.O\n");
+        assert_eq!(
+            render(before, after, Highlight::Ansi),
+            "\x1b[31m.\x1b[0m.#\n\x1b[1;32mO\x1b[0m.O\n"
+        );
+        assert_eq!(render(after, after, Highlight::Marked), after.to_string());
+    }
+
+    #[test]
+    fn t_loads() {
+        let data = &read_file_panic("./data/day14/test.txt");
+        let board = &parse_board(data).unwrap();
+
+        // turning the board around swaps which side is which
+        let flipped = board.rotate_clockwise().rotate_clockwise();
+        assert_eq!(get_load(board, North), get_load(&flipped, South));
+        assert_eq!(get_load(board, East), get_load(&flipped, West));
+
+        let (loads, cycle) = load_history(board, North, 30);
+        assert_eq!(loads.len(), 31);
+        assert_eq!(loads[0], get_load(board, North));
+        assert_eq!((cycle.start, cycle.len()), (3, 7));
+        (cycle.start..=23).for_each(|n| assert_eq!(loads[n], loads[n + cycle.len()]));
+    }
+
     #[test]
     fn d() {
         let data = &read_file_panic("./data/day14/data.txt");
+        let data = &parse_board(data).unwrap();
         let answer = doit(data);
-        assert_eq!(answer, 106186);
+        assert_eq!(answer.to_string(), recorded_answer(14, 1));
 
         let answer = doit2(data);
-        assert_eq!(answer, 0);
+        assert_eq!(answer.to_string(), recorded_answer(14, 2));
     }
 }
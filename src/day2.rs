@@ -1,17 +1,20 @@
-use core::fmt;
 use std::collections::HashMap;
-use std::error::Error;
-use std::fmt::Formatter;
 
 use regex::Regex;
 
-use crate::util::DataLine;
+use crate::error::ParseError;
+use crate::solution::Solution;
+use crate::util::{get_non_empty_lines, DataLine};
 
 trait Parse {
-    fn game_num_and_rest<'a>(&self, line: &'a str) -> Result<(u64, &'a str), Box<dyn Error>>;
+    fn game_num_and_rest<'a>(&self, line: &DataLine<'a>) -> Result<(u64, &'a str), ParseError>;
     fn split_semicolons<'h>(&self, from: &'h str) -> Vec<&'h str>;
     fn split_commas<'h>(&self, from: &'h str) -> Vec<&'h str>;
-    fn get_num_and_color<'a>(&self, text: &'a str) -> Result<(u64, &'a str), Box<dyn Error>>;
+    fn get_num_and_color<'a>(
+        &self,
+        line: &DataLine<'a>,
+        text: &'a str,
+    ) -> Result<(u64, &'a str), ParseError>;
 }
 struct Regexes {
     game: Regex,
@@ -20,9 +23,13 @@ struct Regexes {
     num_and_color: Regex,
 }
 impl Parse for Regexes {
-    fn game_num_and_rest<'a>(&self, line: &'a str) -> Result<(u64, &'a str), Box<dyn Error>> {
-        let (_, [game_num, rest]) = self.game.captures(line).ok_or(ParseError)?.extract();
-        let game_num: u64 = game_num.parse()?;
+    fn game_num_and_rest<'a>(&self, line: &DataLine<'a>) -> Result<(u64, &'a str), ParseError> {
+        let (_, [game_num, rest]) = self
+            .game
+            .captures(line.line)
+            .ok_or_else(|| line.unexpected(line.line, "Game <id>: <draws>"))?
+            .extract();
+        let game_num = line.parse_num(game_num)?;
         Ok((game_num, rest))
     }
 
@@ -34,29 +41,33 @@ impl Parse for Regexes {
         self.comma.split(from).collect()
     }
 
-    fn get_num_and_color<'a>(&self, text: &'a str) -> Result<(u64, &'a str), Box<dyn Error>> {
+    fn get_num_and_color<'a>(
+        &self,
+        line: &DataLine<'a>,
+        text: &'a str,
+    ) -> Result<(u64, &'a str), ParseError> {
         let (_, [num, color]) = self
             .num_and_color
             .captures(text)
-            .ok_or(ParseError)?
+            .ok_or_else(|| line.unexpected(text, "<count> <color>"))?
             .extract();
-        let num: u64 = num.parse()?;
+        let num = line.parse_num(num)?;
         Ok((num, color))
     }
 }
 
-fn create_parse() -> Result<Regexes, Box<dyn Error>> {
-    let game = Regex::new(r"Game (\d+): (.*)")?;
-    let semi = Regex::new(r"\s*;\s*")?;
-    let comma = Regex::new(r"\s*,\s*")?;
-    let num_and_color = Regex::new(r"(\d+)\s+(.*)")?;
+fn create_parse() -> Regexes {
+    let game = Regex::new(r"Game (\d+): (.*)").unwrap();
+    let semi = Regex::new(r"\s*;\s*").unwrap();
+    let comma = Regex::new(r"\s*,\s*").unwrap();
+    let num_and_color = Regex::new(r"(\d+)\s+(.*)").unwrap();
 
-    Ok(Regexes {
+    Regexes {
         game,
         semi,
         comma,
         num_and_color,
-    })
+    }
 }
 
 type Color = str;
@@ -65,22 +76,13 @@ type Draw<'a> = HashMap<&'a Color, Count>;
 type DiceCounts<'a> = Draw<'a>;
 
 #[derive(Debug)]
-struct Game<'a> {
+pub struct Game<'a> {
     id: u64,
     draws: Vec<Draw<'a>>,
 }
 
-#[derive(Debug)]
-struct ParseError;
-impl fmt::Display for ParseError {
-    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "error parsing")
-    }
-}
-impl Error for ParseError {}
-
-fn parse_game<'a>(line: &DataLine<'a>, parser: &impl Parse) -> Result<Game<'a>, Box<dyn Error>> {
-    let (game_num, rest) = parser.game_num_and_rest(line.line)?;
+fn parse_game<'a>(line: &DataLine<'a>, parser: &impl Parse) -> Result<Game<'a>, ParseError> {
+    let (game_num, rest) = parser.game_num_and_rest(line)?;
 
     let draws = parser
         .split_semicolons(rest)
@@ -90,12 +92,12 @@ fn parse_game<'a>(line: &DataLine<'a>, parser: &impl Parse) -> Result<Game<'a>,
                 .split_commas(info)
                 .iter()
                 .try_fold(HashMap::new(), |mut acc, draw| {
-                    let (count, color) = parser.get_num_and_color(draw)?;
+                    let (count, color) = parser.get_num_and_color(line, draw)?;
                     acc.insert(color, count);
                     Ok(acc)
                 })
         })
-        .collect::<Result<Vec<Draw>, Box<dyn Error>>>()?;
+        .collect::<Result<Vec<Draw>, ParseError>>()?;
 
     Ok(Game {
         id: game_num,
@@ -103,15 +105,15 @@ fn parse_game<'a>(line: &DataLine<'a>, parser: &impl Parse) -> Result<Game<'a>,
     })
 }
 
-fn parse_games<'a, I>(lines: I) -> Result<Vec<Game<'a>>, Box<dyn Error>>
+fn parse_games<'a, I>(lines: I) -> Result<Vec<Game<'a>>, ParseError>
 where
     I: IntoIterator<Item = DataLine<'a>>,
 {
-    let parser = create_parse()?;
-    let games = lines.into_iter().map(|line| {
-        parse_game(&line, &parser).unwrap_or_else(|err| panic!("Error for {line}: {err}"))
-    });
-    Ok(games.collect())
+    let parser = create_parse();
+    lines
+        .into_iter()
+        .map(|line| parse_game(&line, &parser))
+        .collect()
 }
 
 fn game_is_possible(game: &Game, dice_counts: &DiceCounts) -> bool {
@@ -159,9 +161,32 @@ fn doit2(games: &[Game]) -> u64 {
     })
 }
 
+pub struct Day2;
+
+impl Solution for Day2 {
+    const DAY: u32 = 2;
+
+    type Input<'a> = Vec<Game<'a>>;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(data: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_games(get_non_empty_lines(data))
+    }
+
+    fn part1(input: &Self::Input<'_>) -> u64 {
+        doit(input)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> u64 {
+        doit2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::util::{get_non_empty_lines, read_file_panic};
+    use crate::util::read_file_panic;
+    use crate::verify::recorded_answer;
 
     use super::*;
 
@@ -183,9 +208,9 @@ mod tests {
         let games = &parse_games(get_non_empty_lines(data)).unwrap();
 
         let answer = doit(games);
-        assert_eq!(answer, 2176);
+        assert_eq!(answer.to_string(), recorded_answer(2, 1));
 
         let answer = doit2(games);
-        assert_eq!(answer, 63700)
+        assert_eq!(answer.to_string(), recorded_answer(2, 2))
     }
 }
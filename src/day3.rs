@@ -1,4 +1,6 @@
-use crate::util::{get_non_empty_lines, DataLine};
+use crate::error::ParseError;
+use crate::solution::Solution;
+use crate::util::{get_non_empty_lines, DataLine, Grid, Pos};
 use regex::Regex;
 use std::collections::{HashMap, HashSet};
 use std::ops::Range;
@@ -10,136 +12,129 @@ struct PartNum {
     num: u64,
 }
 
-#[derive(Debug, Eq, PartialEq, Hash)]
-struct Coord {
-    line_num: usize,
-    idx: usize,
-}
-
-fn surrounding_coords(line_num: usize, indices: &Range<usize>) -> Vec<Coord> {
-    let start = indices.start;
-    let end = indices.end;
-
-    let mut ret: Vec<Coord> = vec![];
-
-    let mut add_above_or_below = |line_num: usize| {
-        let start_idx = if start == 0 { 0 } else { start - 1 };
-        (start_idx..end + 1).for_each(|idx| ret.push(Coord { line_num, idx }));
-    };
-
-    if line_num > 0 {
-        add_above_or_below(line_num - 1);
-    }
-    add_above_or_below(line_num + 1);
-
-    if start > 0 {
-        ret.push(Coord {
-            line_num,
-            idx: start - 1,
-        })
-    }
-    ret.push(Coord { line_num, idx: end });
-
-    ret
-}
-
-fn part_nums_from_lines<'a, I>(lines: I) -> Vec<PartNum>
+/// `line_num` is the row in the board, which skips blank lines
+fn part_nums_from_lines<'a, I>(lines: I) -> Result<Vec<PartNum>, ParseError>
 where
-    I: Iterator<Item = &'a DataLine<'a>>,
+    I: IntoIterator<Item = DataLine<'a>>,
 {
     let num_re = Regex::new(r"\d+").unwrap();
 
-    let part_nums_from_line = |line: &DataLine| {
+    let part_nums_from_line = |(line_num, line): (usize, DataLine<'a>)| {
         num_re
             .find_iter(line.line)
-            .map(|m| PartNum {
-                line_num: line.line_number,
-                indices: m.range(),
-                num: m.as_str().parse().unwrap_or_else(|_| panic!("{line}")),
+            .map(|m| {
+                Ok(PartNum {
+                    line_num,
+                    indices: m.range(),
+                    num: line.parse_num(m.as_str())?,
+                })
             })
             .collect::<Vec<_>>()
     };
 
-    lines.flat_map(part_nums_from_line).collect()
+    lines
+        .into_iter()
+        .enumerate()
+        .flat_map(part_nums_from_line)
+        .collect()
 }
 
-fn doit(data: &str) -> u64 {
-    let lines = get_non_empty_lines(data).collect::<Vec<_>>();
+pub struct Schematic {
+    part_nums: Vec<PartNum>,
+    board: Grid<char>,
+}
 
-    let part_nums = part_nums_from_lines(lines.iter());
+fn parse_schematic(data: &str) -> Result<Schematic, ParseError> {
+    Ok(Schematic {
+        part_nums: part_nums_from_lines(get_non_empty_lines(data))?,
+        board: Grid::parse(data)?,
+    })
+}
 
-    let two_d_board = lines
-        .iter()
-        .map(|line| line.line.chars().collect::<Vec<_>>())
-        .collect::<Vec<_>>();
+impl PartNum {
+    /// every cell touching one of this number's digits, diagonally included
+    fn surrounding<'a>(&'a self, board: &'a Grid<char>) -> impl Iterator<Item = Pos> + 'a {
+        self.indices
+            .clone()
+            .flat_map(move |idx| board.neighbors8((idx, self.line_num)))
+    }
+}
 
-    let char_at = |c: &Coord| {
-        let line = two_d_board.get(c.line_num)?;
-        let &char = line.get(c.idx)?;
-        Some(char)
-    };
+fn doit(schematic: &Schematic) -> u64 {
+    let board = &schematic.board;
 
     let adjacent_to_symbol = |part_num: &PartNum| {
-        surrounding_coords(part_num.line_num, &part_num.indices)
-            .iter()
-            .any(|coord| char_at(coord).is_some_and(|c| !c.is_ascii_digit() && (c != '.')))
+        part_num
+            .surrounding(board)
+            .any(|pos| !board[pos].is_ascii_digit() && board[pos] != '.')
     };
 
-    part_nums
+    schematic
+        .part_nums
         .iter()
         .filter(|&pn| adjacent_to_symbol(pn))
         .fold(0, |acc, pn| acc + pn.num)
 }
 
-fn doit2(data: &str) -> u64 {
-    let lines = get_non_empty_lines(data).collect::<Vec<_>>();
-
-    let part_nums = part_nums_from_lines(lines.iter());
-
-    let mut coords_to_pns: HashMap<Coord, &PartNum> = HashMap::new();
-    part_nums.iter().for_each(|pn| {
+fn doit2(schematic: &Schematic) -> u64 {
+    let mut coords_to_pns: HashMap<Pos, &PartNum> = HashMap::new();
+    schematic.part_nums.iter().for_each(|pn| {
         pn.indices.clone().for_each(|idx| {
-            coords_to_pns.insert(
-                Coord {
-                    line_num: pn.line_num,
-                    idx,
-                },
-                pn,
-            );
+            coords_to_pns.insert((idx, pn.line_num), pn);
         })
     });
 
     let mut total = 0u64;
 
-    lines.iter().for_each(|line| {
-        line.line.char_indices().for_each(|(idx, char)| {
-            if char == '*' {
-                let touching = surrounding_coords(line.line_number, &(idx..idx + 1));
-                let mut parts: HashSet<&PartNum> = HashSet::new();
-                touching.iter().for_each(|c| {
-                    coords_to_pns.get(c).iter().for_each(|pn| {
-                        parts.insert(pn);
-                    });
-                });
-                if parts.len() == 2 {
-                    total += parts.iter().fold(1, |acc, pn| acc * pn.num)
-                }
+    schematic.board.iter().for_each(|(pos, &char)| {
+        if char == '*' {
+            let parts: HashSet<&PartNum> = schematic
+                .board
+                .neighbors8(pos)
+                .filter_map(|c| coords_to_pns.get(&c).copied())
+                .collect();
+            if parts.len() == 2 {
+                total += parts.iter().fold(1, |acc, pn| acc * pn.num)
             }
-        })
+        }
     });
 
     total
 }
 
+pub struct Day3;
+
+impl Solution for Day3 {
+    const DAY: u32 = 3;
+
+    type Input<'a> = Schematic;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(data: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_schematic(data)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> u64 {
+        doit(input)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> u64 {
+        doit2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::util::read_file_panic;
+    use crate::verify::recorded_answer;
 
     use super::*;
 
     #[test]
     fn t() {
         let data = &read_file_panic("./data/day3/test.txt");
+        let data = &parse_schematic(data).unwrap();
         let answer = doit(data);
         assert_eq!(answer, 4361);
 
@@ -150,10 +145,11 @@ mod tests {
     #[test]
     fn d() {
         let data = &read_file_panic("./data/day3/data.txt");
+        let data = &parse_schematic(data).unwrap();
         let answer = doit(data);
-        assert_eq!(answer, 535235);
+        assert_eq!(answer.to_string(), recorded_answer(3, 1));
 
         let answer = doit2(data);
-        assert_eq!(answer, 79844424)
+        assert_eq!(answer.to_string(), recorded_answer(3, 2))
     }
 }
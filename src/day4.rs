@@ -1,49 +1,47 @@
 use std::collections::{HashMap, HashSet};
 
-use regex::{Match, Regex};
+use regex::Regex;
 
-use crate::util::DataLine;
+use crate::error::ParseError;
+use crate::solution::Solution;
+use crate::util::{get_non_empty_lines, DataLine};
 
-struct Card {
+pub struct Card {
     id: u64,
     winning_nums: Vec<u64>,
     nums_you_have: HashSet<u64>,
 }
 
-fn match_to_num(m: Match) -> u64 {
-    m.as_str().parse().unwrap()
-}
-
-fn parse_cards<'a, I>(lines: I) -> Vec<Card>
+fn parse_cards<'a, I>(lines: I) -> Result<Vec<Card>, ParseError>
 where
     I: IntoIterator<Item = DataLine<'a>>,
 {
     let line_re = Regex::new(r"Card\s+(\d+): (.*) \| (.*)").unwrap();
     let nums_re = Regex::new(r"\d+").unwrap();
 
-    let parse_card_opt = |line: &DataLine| {
-        let caps = line_re.captures(line.line)?;
+    let parse_card = |line: &DataLine<'a>| {
+        let caps = line_re
+            .captures(line.line)
+            .ok_or_else(|| line.unexpected(line.line, "Card <id>: <nums> | <nums>"))?;
         let (_, [id, winning, ours]) = caps.extract();
-        let id = id.parse().ok()?;
+        let id = line.parse_num(id)?;
 
         let winning_nums = nums_re
             .find_iter(winning)
-            .map(match_to_num)
-            .collect::<Vec<_>>();
+            .map(|m| line.parse_num(m.as_str()))
+            .collect::<Result<Vec<_>, _>>()?;
         let nums_you_have = nums_re
             .find_iter(ours)
-            .map(match_to_num)
-            .collect::<HashSet<_>>();
+            .map(|m| line.parse_num(m.as_str()))
+            .collect::<Result<HashSet<_>, _>>()?;
 
-        Some(Card {
+        Ok(Card {
             id,
             winning_nums,
             nums_you_have,
         })
     };
 
-    let parse_card = |line: &DataLine| parse_card_opt(line).unwrap_or_else(|| panic!("{line}"));
-
     lines.into_iter().map(|line| parse_card(&line)).collect()
 }
 
@@ -95,9 +93,32 @@ fn doit2(cards: &[Card]) -> u64 {
     id_to_num_owned.values().sum()
 }
 
+pub struct Day4;
+
+impl Solution for Day4 {
+    const DAY: u32 = 4;
+
+    type Input<'a> = Vec<Card>;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(data: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_cards(get_non_empty_lines(data))
+    }
+
+    fn part1(input: &Self::Input<'_>) -> u64 {
+        doit(input)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> u64 {
+        doit2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::util::{get_non_empty_lines, read_file_panic};
+    use crate::util::read_file_panic;
+    use crate::verify::recorded_answer;
 
     use super::*;
 
@@ -105,7 +126,7 @@ mod tests {
     fn t() {
         let data = &read_file_panic("./data/day4/test.txt");
         let lines = get_non_empty_lines(data);
-        let cards = &parse_cards(lines);
+        let cards = &parse_cards(lines).unwrap();
 
         let answer = doit(cards);
         assert_eq!(answer, 13);
@@ -118,12 +139,12 @@ mod tests {
     fn d() {
         let data = &read_file_panic("./data/day4/data.txt");
         let lines = get_non_empty_lines(data);
-        let cards = &parse_cards(lines);
+        let cards = &parse_cards(lines).unwrap();
 
         let answer = doit(cards);
-        assert_eq!(answer, 20407);
+        assert_eq!(answer.to_string(), recorded_answer(4, 1));
 
         let answer = doit2(cards);
-        assert_eq!(answer, 23806951)
+        assert_eq!(answer.to_string(), recorded_answer(4, 2))
     }
 }
@@ -2,6 +2,8 @@ use rangemap::RangeMap;
 use regex::Regex;
 use std::ops::Range;
 
+use crate::error::{missing_input, ParseError};
+use crate::solution::Solution;
 use crate::util::get_lines;
 
 type Delta = i64;
@@ -13,7 +15,7 @@ struct Map<'a> {
     range_map: RangeMap<i64, Delta>,
 }
 #[derive(Debug)]
-struct Almanac<'a> {
+pub struct Almanac<'a> {
     seeds: Vec<i64>,
     maps: Vec<Map<'a>>,
 }
@@ -24,58 +26,64 @@ fn default_range_map() -> RangeMap<i64, Delta> {
     ret
 }
 
-fn parse_almanac(data: &str) -> Almanac {
-    let lines = get_lines(data);
+fn parse_almanac(data: &str) -> Result<Almanac<'_>, ParseError> {
+    let mut lines = get_lines(data);
 
     let seeds_re = Regex::new(r"seeds: (.*)").unwrap();
     let num_re = Regex::new(r"\d+").unwrap();
     let map_header_re = Regex::new(r"(.+) map:").unwrap();
     let map_ent_re = Regex::new(r"(\d+)\s+(\d+)\s+(\d+)").unwrap();
 
-    let mut seeds: Vec<i64> = vec![];
+    let first = lines
+        .next()
+        .ok_or_else(|| missing_input("seeds: <seeds>"))?;
+    let (_, [rest]) = seeds_re
+        .captures(first.line)
+        .ok_or_else(|| first.unexpected(first.line, "seeds: <seeds>"))?
+        .extract();
+    let seeds = num_re
+        .find_iter(rest)
+        .map(|m| first.parse_num(m.as_str()))
+        .collect::<Result<Vec<i64>, _>>()?;
+
     let mut maps: Vec<Map> = vec![];
     let mut curr_map: Option<Map> = None;
 
-    lines.for_each(|line| {
-        let to_num = |str: &str| -> i64 { str.parse().unwrap_or_else(|e| panic!("{line}, {e}")) };
-
-        if line.line_number == 0 {
-            let (_, [rest]) = seeds_re.captures(line.line).unwrap().extract();
-            num_re
-                .find_iter(rest)
-                .for_each(|m| seeds.push(m.as_str().parse().unwrap()));
-        } else if line.line_number > 1 {
-            if line.line.trim().is_empty() {
-                if let Some(m) = curr_map.take() {
-                    maps.push(m);
+    for line in lines.skip(1) {
+        if line.line.trim().is_empty() {
+            if let Some(m) = curr_map.take() {
+                maps.push(m);
+            }
+        } else {
+            match map_header_re.captures(line.line) {
+                Some(cap) => {
+                    let (_, [name]) = cap.extract();
+                    curr_map = Some(Map {
+                        name,
+                        range_map: default_range_map(),
+                    });
                 }
-            } else {
-                match map_header_re.captures(line.line) {
-                    Some(cap) => {
-                        let (_, [name]) = cap.extract();
-                        curr_map = Some(Map {
-                            name,
-                            range_map: default_range_map(),
-                        });
-                    }
-
-                    None => {
-                        let (_, [dest, src, count]) = map_ent_re
-                            .captures(line.line)
-                            .unwrap_or_else(|| panic!("{line}"))
-                            .extract();
-                        let dest = to_num(dest);
-                        let src = to_num(src);
-
-                        let map = curr_map.as_mut().unwrap_or_else(|| panic!("{line}"));
-                        map.range_map.insert(src..(src + to_num(count)), dest - src);
-                    }
+
+                None => {
+                    let (_, [dest, src, count]) = map_ent_re
+                        .captures(line.line)
+                        .ok_or_else(|| line.unexpected(line.line, "<dest> <src> <count>"))?
+                        .extract();
+                    let dest: i64 = line.parse_num(dest)?;
+                    let src: i64 = line.parse_num(src)?;
+                    let count: i64 = line.parse_num(count)?;
+
+                    let map = curr_map
+                        .as_mut()
+                        .ok_or_else(|| line.unexpected(line.line, "<name> map:"))?;
+                    map.range_map.insert(src..(src + count), dest - src);
                 }
             }
         }
-    });
+    }
+    maps.extend(curr_map);
 
-    Almanac { seeds, maps }
+    Ok(Almanac { seeds, maps })
 }
 
 fn find_location(seed: i64, almanac: &Almanac) -> i64 {
@@ -130,16 +138,39 @@ fn doit2(almanac: &Almanac) -> i64 {
     })
 }
 
+pub struct Day5;
+
+impl Solution for Day5 {
+    const DAY: u32 = 5;
+
+    type Input<'a> = Almanac<'a>;
+    type Answer1 = i64;
+    type Answer2 = i64;
+
+    fn parse(data: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_almanac(data)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> i64 {
+        doit(input)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> i64 {
+        doit2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::util::{print_dur, read_file_panic};
+    use crate::verify::recorded_answer;
 
     use super::*;
 
     #[test]
     fn t() {
         let data = &read_file_panic("./data/day5/test.txt");
-        let almanac = &parse_almanac(data);
+        let almanac = &parse_almanac(data).unwrap();
         let answer = doit(almanac);
         assert_eq!(answer, 35);
 
@@ -150,12 +181,32 @@ mod tests {
     #[test]
     fn d() {
         let data = &read_file_panic("./data/day5/data.txt");
-        let almanac = &print_dur("parsed almanac", || parse_almanac(data));
+        let almanac = &print_dur("parsed almanac", || parse_almanac(data)).unwrap();
 
         let answer = print_dur("did part 1", || doit(almanac));
-        assert_eq!(answer, 178159714);
+        assert_eq!(answer.to_string(), recorded_answer(5, 1));
 
         let answer = print_dur("did part 2", || doit2(almanac));
-        assert_eq!(answer, 100165128);
+        assert_eq!(answer.to_string(), recorded_answer(5, 2));
+    }
+
+    #[test]
+    fn bad_input() {
+        let err = parse_almanac("seeds: 79 14\n\nseed-to-soil map:\n50 98 x2\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            r#"Line 4, column 1: expected <dest> <src> <count>, found "50 98 x2""#
+        );
+
+        let err = parse_almanac("seeds: 79 14\n\n50 98 2\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            r#"Line 3, column 1: expected <name> map:, found "50 98 2""#
+        );
+
+        assert!(matches!(
+            parse_almanac(""),
+            Err(ParseError::Unexpected { .. })
+        ));
     }
 }
@@ -1,57 +1,120 @@
 use regex::Regex;
 
+use crate::error::{missing_input, ParseError};
+use crate::solution::Solution;
+use crate::util::{get_non_empty_lines, DataLine};
+
 #[derive(Debug)]
 struct Record {
     time: u64,
     distance: u64,
 }
 
-fn parse_data(data: &str) -> Vec<Record> {
-    let num_re = Regex::new(r"\d+").unwrap();
-    let (times, distances) = data.split_once('\n').unwrap();
-    let i = |s: &str| -> u64 { s.parse().unwrap() };
-
-    num_re
-        .find_iter(times)
-        .zip(num_re.find_iter(distances))
-        .map(|(t, d)| Record {
-            time: i(t.as_str()),
-            distance: i(d.as_str()),
-        })
-        .collect()
+#[derive(Debug)]
+pub struct Races {
+    records: Vec<Record>,
+    /// part 2 reads each line as one number, ignoring the spaces
+    kerned: Record,
 }
 
+fn parse_data(data: &str) -> Result<Races, ParseError> {
+    let num_re = Regex::new(r"\S+").unwrap();
+    let mut lines = get_non_empty_lines(data);
+    let times = lines.next().ok_or_else(|| missing_input("Time: <times>"))?;
+    let distances = lines
+        .next()
+        .ok_or_else(|| missing_input("Distance: <distances>"))?;
+
+    fn values<'a>(line: &DataLine<'a>, label: &str) -> Result<&'a str, ParseError> {
+        line.line
+            .strip_prefix(label)
+            .ok_or_else(|| line.unexpected(line.line, label))
+    }
+    let nums = |line: &DataLine<'_>, label: &str| {
+        num_re
+            .find_iter(values(line, label)?)
+            .map(|m| line.parse_num(m.as_str()))
+            .collect::<Result<Vec<u64>, _>>()
+    };
+    let kerned = |line: &DataLine<'_>, label: &str| {
+        let values = values(line, label)?;
+        values
+            .split_whitespace()
+            .collect::<String>()
+            .parse::<u64>()
+            .map_err(|_| line.unexpected(values, "a number"))
+    };
+
+    let time_nums = nums(&times, "Time:")?;
+    let distance_nums = nums(&distances, "Distance:")?;
+    if time_nums.len() != distance_nums.len() {
+        let expected = format!("{} distances", time_nums.len());
+        return Err(distances.unexpected(distances.line, &expected));
+    }
+
+    let records = time_nums
+        .into_iter()
+        .zip(distance_nums)
+        .map(|(time, distance)| Record { time, distance })
+        .collect();
+
+    Ok(Races {
+        records,
+        kerned: Record {
+            time: kerned(&times, "Time:")?,
+            distance: kerned(&distances, "Distance:")?,
+        },
+    })
+}
+
+/// none at all if even the best hold time can't beat the record
 fn number_of_ways_to_win(prev_rec: &Record) -> u64 {
-    let num_skipped = (0..prev_rec.time)
+    (0..prev_rec.time)
         .find(|&i| (i * (prev_rec.time - i)) > prev_rec.distance)
-        .unwrap();
-    (prev_rec.time + 1) - (num_skipped * 2)
+        .map_or(0, |num_skipped| (prev_rec.time + 1) - (num_skipped * 2))
+}
+
+fn doit(races: &Races) -> u64 {
+    races.records.iter().map(number_of_ways_to_win).product()
 }
 
-fn doit(data: &str) -> u64 {
-    parse_data(data).iter().map(number_of_ways_to_win).product()
+fn doit2(races: &Races) -> u64 {
+    number_of_ways_to_win(&races.kerned)
 }
 
-fn doit2(data: &str) -> u64 {
-    let num_re = Regex::new(r"\d+").unwrap();
-    let (time, distance) = data.split_once('\n').unwrap();
-    let i = |s: String| -> u64 { s.parse().unwrap() };
+pub struct Day6;
+
+impl Solution for Day6 {
+    const DAY: u32 = 6;
+
+    type Input<'a> = Races;
+    type Answer1 = u64;
+    type Answer2 = u64;
 
-    let time = i(num_re.find_iter(time).map(|m| m.as_str()).collect());
-    let distance = i(num_re.find_iter(distance).map(|m| m.as_str()).collect());
+    fn parse(data: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_data(data)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> u64 {
+        doit(input)
+    }
 
-    number_of_ways_to_win(&Record { time, distance })
+    fn part2(input: &Self::Input<'_>) -> u64 {
+        doit2(input)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::util::read_file_panic;
+    use crate::verify::recorded_answer;
 
     use super::*;
 
     #[test]
     fn t() {
         let data = &read_file_panic("./data/day6/test.txt");
+        let data = &parse_data(data).unwrap();
         let answer = doit(data);
         assert_eq!(answer, 288);
 
@@ -59,13 +122,23 @@ mod tests {
         assert_eq!(answer, 71503)
     }
 
+    #[test]
+    fn t_unbeatable() {
+        let data = &parse_data("Time: 7 30\nDistance: 100 200\n").unwrap();
+        assert_eq!(doit(data), 0);
+
+        // read as one race, 730 can beat 100200 by holding anywhere from 184 to 546
+        assert_eq!(doit2(data), 546 - 184 + 1);
+    }
+
     #[test]
     fn d() {
         let data = &read_file_panic("./data/day6/data.txt");
+        let data = &parse_data(data).unwrap();
         let answer = doit(data);
-        assert_eq!(answer, 2344708);
+        assert_eq!(answer.to_string(), recorded_answer(6, 1));
 
         let answer = doit2(data);
-        assert_eq!(answer, 30125202)
+        assert_eq!(answer.to_string(), recorded_answer(6, 2))
     }
 }
@@ -7,7 +7,9 @@ use regex::Regex;
 use crate::day7::HandType::{
     FiveOfKind, FourOfKind, FullHouse, HighCard, OnePair, ThreeOfKind, TwoPair,
 };
-use crate::util::get_non_empty_lines;
+use crate::error::ParseError;
+use crate::solution::Solution;
+use crate::util::{get_non_empty_lines, par_map};
 
 #[derive(Debug, Eq, PartialEq, Hash)]
 enum HandType {
@@ -52,8 +54,8 @@ lazy_static! {
     ]);
 }
 
-#[derive(Debug)]
-struct RawHand<'a> {
+#[derive(Debug, Clone, Copy)]
+pub struct RawHand<'a> {
     cards: &'a str,
     bid: u64,
 }
@@ -69,23 +71,27 @@ struct HandAndType<'a> {
     hand_type: HandTypeAndPower,
 }
 
-fn parse_hands(data: &str) -> Vec<RawHand> {
-    let re = Regex::new(r"(.{5}) (\d+)").unwrap();
-    let lines = get_non_empty_lines(data);
+fn parse_hands(data: &str) -> Result<Vec<RawHand<'_>>, ParseError> {
+    let re = Regex::new(r"^(\S{5}) (\d+)$").unwrap();
+    let lines = get_non_empty_lines(data).collect::<Vec<_>>();
 
-    lines
-        .map(|line| {
-            let (_, [cards, bid]) = re
-                .captures(line.line)
-                .unwrap_or_else(|| panic!("{line}"))
-                .extract();
+    par_map(&lines, |line| {
+        let (_, [cards, bid]) = re
+            .captures(line.line)
+            .ok_or_else(|| line.unexpected(line.line, "<5 cards> <bid>"))?
+            .extract();
 
-            RawHand {
-                cards,
-                bid: bid.parse().unwrap_or_else(|e| panic!("{e}: {line}")),
-            }
+        if let Some((idx, _)) = cards.char_indices().find(|(_, c)| !CARD_ORDER.contains(c)) {
+            return Err(line.unexpected(line.char_at(line.column_of(cards) + idx), "a card"));
+        }
+
+        Ok(RawHand {
+            cards,
+            bid: line.parse_num(bid)?,
         })
-        .collect()
+    })
+    .into_iter()
+    .collect()
 }
 
 fn get_type(cards: &str) -> HandTypeAndPower {
@@ -127,14 +133,13 @@ fn get_type(cards: &str) -> HandTypeAndPower {
     HandTypeAndPower { typ, power }
 }
 
-fn doit_impl<F>(data: &str, enrich: F, card_power_map: &HashMap<char, u32>) -> u64
+fn doit_impl<F>(hands: &[RawHand], enrich: F, card_power_map: &HashMap<char, u32>) -> u64
 where
     F: FnMut(RawHand) -> HandAndType,
 {
     let card_to_power = |card| *card_power_map.get(&card).unwrap();
 
-    let hands = parse_hands(data);
-    let mut hands: Vec<_> = hands.into_iter().map(enrich).collect();
+    let mut hands: Vec<_> = hands.iter().copied().map(enrich).collect();
 
     hands.sort_by(|a, b| {
         let ord = a.hand_type.power.cmp(&b.hand_type.power);
@@ -156,13 +161,13 @@ where
         .sum()
 }
 
-fn doit(data: &str) -> u64 {
+fn doit(hands: &[RawHand]) -> u64 {
     fn enrich(hand: RawHand) -> HandAndType {
         let hand_type = get_type(hand.cards);
         HandAndType { hand, hand_type }
     }
 
-    doit_impl(data, enrich, &CARD_POWER)
+    doit_impl(hands, enrich, &CARD_POWER)
 }
 
 fn get_possible_hands(non_jokers: &HashSet<char>, hand: &str) -> Vec<String> {
@@ -186,7 +191,7 @@ fn get_possible_hands(non_jokers: &HashSet<char>, hand: &str) -> Vec<String> {
     rec(non_jokers, String::new(), &hand.chars().collect::<Vec<_>>())
 }
 
-fn doit2(data: &str) -> u64 {
+fn doit2(hands: &[RawHand]) -> u64 {
     fn replace_jokers(hand: &RawHand) -> Vec<HandTypeAndPower> {
         let mut cards_in_hand: HashSet<_> = hand.cards.chars().collect();
         if !cards_in_hand.contains(&'J') {
@@ -218,32 +223,71 @@ fn doit2(data: &str) -> u64 {
         }
     }
 
-    doit_impl(data, enrich, &PART2_CARD_POWER)
+    doit_impl(hands, enrich, &PART2_CARD_POWER)
+}
+
+pub struct Day7;
+
+impl Solution for Day7 {
+    const DAY: u32 = 7;
+
+    type Input<'a> = Vec<RawHand<'a>>;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(data: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_hands(data)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> u64 {
+        doit(input)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> u64 {
+        doit2(input)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::util::read_file_panic;
+    use crate::verify::recorded_answer;
 
     use super::*;
 
     #[test]
     fn t() {
         let data = &read_file_panic("./data/day7/test.txt");
-        let answer = doit(data);
+        let hands = &parse_hands(data).unwrap();
+        let answer = doit(hands);
         assert_eq!(answer, 6440);
 
-        let answer = doit2(data);
+        let answer = doit2(hands);
         assert_eq!(answer, 5905)
     }
 
     #[test]
     fn d() {
         let data = &read_file_panic("./data/day7/data.txt");
-        let answer = doit(data);
-        assert_eq!(answer, 246163188);
+        let hands = &parse_hands(data).unwrap();
+        let answer = doit(hands);
+        assert_eq!(answer.to_string(), recorded_answer(7, 1));
 
-        let answer = doit2(data);
-        assert_eq!(answer, 245794069)
+        let answer = doit2(hands);
+        assert_eq!(answer.to_string(), recorded_answer(7, 2))
+    }
+
+    #[test]
+    fn bad_input() {
+        let err = parse_hands("32T3K 765\nT55X5 684\n").unwrap_err();
+        assert_eq!(
+            err,
+            ParseError::Unexpected {
+                line_number: 1,
+                column: 3,
+                expected: String::from("a card"),
+                found: String::from("X"),
+            }
+        );
     }
 }
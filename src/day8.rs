@@ -1,6 +1,8 @@
+use crate::error::{missing_input, not_found, ParseError};
+use crate::solution::Solution;
 use crate::util::get_non_empty_lines;
 use regex::Regex;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::iter::Cycle;
 use std::str::Chars;
 use tailcall::tailcall;
@@ -11,34 +13,63 @@ struct Elems<'a> {
     right: &'a str,
 }
 #[derive(Debug)]
-struct Map<'a> {
+pub struct Map<'a> {
     instructions: &'a str,
     nodes: HashMap<&'a str, Elems<'a>>,
 }
 
-fn parse_map(data: &str) -> Map {
+fn parse_map(data: &str) -> Result<Map<'_>, ParseError> {
     let elems_re = Regex::new(r"(.{3}) = \((.{3}), (.{3})\)").unwrap();
 
     let mut lines = get_non_empty_lines(data);
 
-    let instructions = lines.next().unwrap().line;
+    let first = lines
+        .next()
+        .ok_or_else(|| missing_input("L/R instructions"))?;
+    if let Some(idx) = first.line.find(|c| c != 'L' && c != 'R') {
+        return Err(first.unexpected(first.char_at(idx), "L or R"));
+    }
+    let instructions = first.line;
 
-    let nodes: HashMap<_, _> = lines
+    let lines = lines
         .map(|line| {
             let (_, [label, left, right]) = elems_re
                 .captures(line.line)
-                .unwrap_or_else(|| panic!("{line}"))
+                .ok_or_else(|| line.unexpected(line.line, "AAA = (BBB, CCC)"))?
                 .extract();
             let elems = Elems { left, right };
 
-            (label, elems)
+            Ok((line, label, elems))
         })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // every node we can be sent to has to be there, or walking the map would get stuck
+    let labels = lines
+        .iter()
+        .map(|(_, label, _)| *label)
+        .collect::<HashSet<_>>();
+    for (line, _, elems) in &lines {
+        if let Some(&missing) = [elems.left, elems.right]
+            .iter()
+            .find(|&n| !labels.contains(n))
+        {
+            return Err(ParseError::Invalid {
+                line_number: line.line_number,
+                column: line.column_of(missing),
+                problem: format!("there's no node {missing}"),
+            });
+        }
+    }
+
+    let nodes = lines
+        .into_iter()
+        .map(|(_, label, elems)| (label, elems))
         .collect();
 
-    Map {
+    Ok(Map {
         instructions,
         nodes,
-    }
+    })
 }
 
 fn get_num_steps<F>(map: &Map, start: &str, is_end_point: F) -> u64
@@ -108,21 +139,68 @@ fn doit2(map: &Map) -> u64 {
     lens.reduce(lcm).unwrap()
 }
 
+fn require_node<F>(map: &Map, expected: &str, matches: F) -> Result<(), ParseError>
+where
+    F: Fn(&str) -> bool,
+{
+    if map.nodes.keys().any(|n| matches(n)) {
+        Ok(())
+    } else {
+        Err(not_found(expected))
+    }
+}
+
+pub struct Day8;
+
+impl Solution for Day8 {
+    const DAY: u32 = 8;
+
+    type Input<'a> = Map<'a>;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(data: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_map(data)
+    }
+
+    fn check1(input: &Self::Input<'_>) -> Result<(), ParseError> {
+        // part 2's example has no AAA, so only part 1 can insist on it. without an end to
+        // reach, the walk would never finish
+        require_node(input, "node 'AAA'", |n| n == "AAA")?;
+        require_node(input, "node 'ZZZ'", |n| n == "ZZZ")
+    }
+
+    fn check2(input: &Self::Input<'_>) -> Result<(), ParseError> {
+        require_node(input, "node ending in 'A'", |n| n.ends_with('A'))?;
+        require_node(input, "node ending in 'Z'", |n| n.ends_with('Z'))
+    }
+
+    fn part1(input: &Self::Input<'_>) -> u64 {
+        doit(input)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> u64 {
+        doit2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use crate::solution::Runner;
     use crate::util::read_file_panic;
+    use crate::verify::recorded_answer;
 
     use super::*;
 
     #[test]
     fn t1() {
         let data = &read_file_panic("./data/day8/part1/test1.txt");
-        let map = &parse_map(data);
+        let map = &parse_map(data).unwrap();
         let answer = doit(map);
         assert_eq!(answer, 2);
 
         let data = &read_file_panic("./data/day8/part1/test2.txt");
-        let map = &parse_map(data);
+        let map = &parse_map(data).unwrap();
         let answer = doit(map);
         assert_eq!(answer, 6);
     }
@@ -130,19 +208,49 @@ mod tests {
     #[test]
     fn t2() {
         let data = &read_file_panic("./data/day8/part2/test.txt");
-        let map = &parse_map(data);
+        let map = &parse_map(data).unwrap();
         let answer = doit2(map);
         assert_eq!(answer, 6);
     }
 
+    #[test]
+    fn bad_input() {
+        let err = parse_map("LR\n\nAAA = (BBB, BBB)\n").unwrap_err();
+        assert_eq!(err.to_string(), "Line 3, column 8: there's no node BBB");
+
+        let err = parse_map("LR\n\nAAA = (AAA, ZZZ)\nZZZ = (ZZZ, CCC)\n").unwrap_err();
+        assert_eq!(err.to_string(), "Line 4, column 13: there's no node CCC");
+
+        let data = &read_file_panic("./data/day8/part2/test.txt");
+        let map = &parse_map(data).unwrap();
+        assert_eq!(Day8::check1(map), Err(not_found("node 'AAA'")));
+        assert_eq!(Day8.run(data, 2).unwrap(), Some(String::from("6")));
+        assert!(Day8.run(data, 1).is_err());
+
+        let err = Day8.run("L\n\nAAA = (AAA, AAA)\n", 1).unwrap_err();
+        assert_eq!(err.to_string(), "there's no node 'ZZZ' in the input");
+        let answer = Day8.run("L\n\nAAA = (BBZ, BBZ)\nBBZ = (BBZ, BBZ)\n", 2);
+        assert_eq!(answer, Ok(Some(String::from("1"))));
+        let err = Day8.run("L\n\n11A = (11A, 11A)\n", 2).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "there's no node ending in 'Z' in the input"
+        );
+        let err = Day8.run("L\n\n11Z = (11Z, 11Z)\n", 2).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "there's no node ending in 'A' in the input"
+        );
+    }
+
     #[test]
     fn d() {
         let data = &read_file_panic("./data/day8/data.txt");
-        let map = &parse_map(data);
+        let map = &parse_map(data).unwrap();
         let answer = doit(map);
-        assert_eq!(answer, 11309);
+        assert_eq!(answer.to_string(), recorded_answer(8, 1));
 
         let answer = doit2(map);
-        assert_eq!(answer, 13740108158591);
+        assert_eq!(answer.to_string(), recorded_answer(8, 2));
     }
 }
@@ -1,11 +1,13 @@
-use regex::{Match, Regex};
+use regex::Regex;
 
-use crate::util::get_non_empty_lines;
+use crate::error::ParseError;
+use crate::solution::Solution;
+use crate::util::{get_non_empty_lines, par_map};
 
 fn calculate_impl(history: Vec<i64>, next: bool) -> i64 {
     let mut seqs = vec![history];
 
-    fn get_diffs(v: &Vec<i64>) -> Vec<i64> {
+    fn get_diffs(v: &[i64]) -> Vec<i64> {
         (0..(v.len() - 1)).map(|idx| v[idx + 1] - v[idx]).collect()
     }
 
@@ -37,47 +39,72 @@ fn calculate_impl(history: Vec<i64>, next: bool) -> i64 {
     curr
 }
 
-fn parse_lines(data: &str) -> Vec<Vec<i64>> {
+fn parse_lines(data: &str) -> Result<Vec<Vec<i64>>, ParseError> {
     let lines = get_non_empty_lines(data);
 
-    let num_re = Regex::new(r"-?\d+").unwrap();
-    let i = |m: Match| m.as_str().parse().ok();
+    let num_re = Regex::new(r"\S+").unwrap();
 
     lines
         .map(|line| {
-            num_re
+            let history = num_re
                 .find_iter(line.line)
-                .map(i)
-                .map(|opt| opt.unwrap_or_else(|| panic!("{line}")))
-                .collect()
+                .map(|m| line.parse_num(m.as_str()))
+                .collect::<Result<Vec<_>, _>>()?;
+            if history.is_empty() {
+                return Err(line.unexpected(line.line, "a number"));
+            }
+            Ok(history)
         })
         .collect()
 }
 
-fn doit_impl(data: &str, next: bool) -> i64 {
-    parse_lines(data)
+fn doit_impl(histories: &[Vec<i64>], next: bool) -> i64 {
+    par_map(histories, |x| calculate_impl(x.clone(), next))
         .into_iter()
-        .map(|x| calculate_impl(x, next))
         .sum()
 }
 
-fn doit(data: &str) -> i64 {
-    doit_impl(data, true)
+fn doit(histories: &[Vec<i64>]) -> i64 {
+    doit_impl(histories, true)
 }
 
-fn doit2(data: &str) -> i64 {
-    doit_impl(data, false)
+fn doit2(histories: &[Vec<i64>]) -> i64 {
+    doit_impl(histories, false)
+}
+
+pub struct Day9;
+
+impl Solution for Day9 {
+    const DAY: u32 = 9;
+
+    type Input<'a> = Vec<Vec<i64>>;
+    type Answer1 = i64;
+    type Answer2 = i64;
+
+    fn parse(data: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_lines(data)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> i64 {
+        doit(input)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> i64 {
+        doit2(input)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::util::read_file_panic;
+    use crate::verify::recorded_answer;
 
     use super::*;
 
     #[test]
     fn t1() {
         let data = &read_file_panic("./data/day9/test.txt");
+        let data = &parse_lines(data).unwrap();
         let answer = doit(data);
         assert_eq!(answer, 114);
     }
@@ -85,17 +112,34 @@ mod tests {
     #[test]
     fn t2() {
         let data = &read_file_panic("./data/day9/test.txt");
+        let data = &parse_lines(data).unwrap();
         let answer = doit2(data);
         assert_eq!(answer, 2);
     }
 
+    #[test]
+    fn bad_input() {
+        let err = parse_lines("1 2 3\n \n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            r#"Line 2, column 1: expected a number, found " ""#
+        );
+
+        let err = parse_lines("1 2 3\n4 x\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            r#"Line 2, column 3: expected a number, found "x""#
+        );
+    }
+
     #[test]
     fn d() {
         let data = &read_file_panic("./data/day9/data.txt");
+        let data = &parse_lines(data).unwrap();
         let answer = doit(data);
-        assert_eq!(answer, 1696140818);
+        assert_eq!(answer.to_string(), recorded_answer(9, 1));
 
         let answer = doit2(data);
-        assert_eq!(answer, 1152);
+        assert_eq!(answer.to_string(), recorded_answer(9, 2));
     }
 }
@@ -0,0 +1,105 @@
+use std::fmt;
+use std::fmt::Formatter;
+
+#[derive(Debug, PartialEq)]
+pub enum ParseError {
+    /// `found` showed up where we wanted `expected`. `line_number` and `column` are 0-based,
+    /// same as [crate::util::DataLine]
+    Unexpected {
+        line_number: usize,
+        column: usize,
+        expected: String,
+        found: String,
+    },
+    /// the input ran out before we found `expected`
+    MissingInput { expected: String },
+    /// `expected` should have been somewhere in the input, but wasn't
+    NotFound { expected: String },
+    /// everything parsed, but doesn't make sense as a whole, starting at this line and column
+    Invalid {
+        line_number: usize,
+        column: usize,
+        problem: String,
+    },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Unexpected {
+                line_number,
+                column,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Line {}, column {}: expected {expected}, found {found:?}",
+                line_number + 1,
+                column + 1
+            ),
+            ParseError::MissingInput { expected } => {
+                write!(f, "expected {expected}, but the input ended")
+            }
+            ParseError::NotFound { expected } => write!(f, "there's no {expected} in the input"),
+            ParseError::Invalid {
+                line_number,
+                column,
+                problem,
+            } => write!(
+                f,
+                "Line {}, column {}: {problem}",
+                line_number + 1,
+                column + 1
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+pub fn missing_input(expected: &str) -> ParseError {
+    ParseError::MissingInput {
+        expected: String::from(expected),
+    }
+}
+
+pub fn not_found(expected: &str) -> ParseError {
+    ParseError::NotFound {
+        expected: String::from(expected),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn t() {
+        let e = ParseError::Unexpected {
+            line_number: 2,
+            column: 0,
+            expected: String::from("a number"),
+            found: String::from("x"),
+        };
+        assert_eq!(
+            e.to_string(),
+            r#"Line 3, column 1: expected a number, found "x""#
+        );
+
+        let e = ParseError::Invalid {
+            line_number: 0,
+            column: 4,
+            problem: String::from("the loop breaks"),
+        };
+        assert_eq!(e.to_string(), "Line 1, column 5: the loop breaks");
+
+        assert_eq!(
+            missing_input("a map").to_string(),
+            "expected a map, but the input ended"
+        );
+        assert_eq!(
+            not_found("node 'AAA'").to_string(),
+            "there's no node 'AAA' in the input"
+        );
+    }
+}
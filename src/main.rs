@@ -1,3 +1,16 @@
+use std::error::Error;
+use std::io::Write;
+use std::path::Path;
+use std::{env, fs, io, process};
+
+use crate::cli::Args;
+use crate::day13::{DiagonalMirror, MirrorPoint};
+use crate::day14::Highlight;
+use crate::solution::Runner;
+use crate::util::Dir;
+
+mod bench;
+mod cli;
 mod day1;
 mod day10;
 mod day11;
@@ -12,8 +25,327 @@ mod day6;
 mod day7;
 mod day8;
 mod day9;
+mod error;
+mod solution;
 mod util;
+mod verify;
+
+const USAGE: &str = "usage:
+  aoc2023 run --day <day> --part <1|2> [--input <path>]
+  aoc2023 verify [--day <day>] [--data-dir <dir>]
+  aoc2023 bench [--day <day>] [--data-dir <dir>] [--warmup <n>] [--iterations <n>]
+                [--format <table|csv|json>]
+  aoc2023 tilt --sequence <NSEW...> [--count <n>] [--render <plain|ansi|marked>]
+               [--input <path>]
+  aoc2023 loads --cycles <n> [--side <N|S|E|W>] [--input <path>]
+  aoc2023 unfold --copies <n> [--separator <.|#|?>] [--input <path>]
+  aoc2023 arrangements --row <springs groups> [--skip <n>] [--limit <n>]
+  aoc2023 mirrors [--smudges <n>] [--input <path>]
+  aoc2023 mirror --block <n> [--smudges <n>] [--ansi] [--input <path>]
+  aoc2023 pipes [--list] [--input <path>]
+  aoc2023 loops [--start <x,y>] [--input <path>]
+  aoc2023 distances [--format <text|ppm>] [--farthest] [--input <path>]
+
+run reads the puzzle input from --input, or stdin if it's missing or '-'.
+verify checks each day against data/day<N>/answers.txt.
+bench times parsing and each part of every day's data.txt separately.
+tilt runs a day 14 board through the sequence of tilts --count times, printing the load after
+each one, and with --render the board too, highlighting the rocks that just moved.
+loads prints the load on a day 14 board's --side (north by default) after each spin cycle, as
+csv.
+unfold totals the day 12 arrangements after joining --copies of each row with --separator
+('?' by default).
+arrangements lists the ways to fill in a single day 12 row, --limit (20 by default) at a time
+after skipping the first --skip.
+mirrors lists every reflection line in each day 13 block, with exactly --smudges (0 by default)
+cells flipped, then every square part of it that reflects diagonally, starting from the cell at
+the top of the mirror, then any way the whole block turns onto itself.
+mirror draws the day 13 --block (counting from 0) with the line the puzzle picks marked, and any
+smudges on it highlighted.
+pipes draws the day 10 loop, with the tiles it encloses marked 'I', or with --list prints the
+enclosed tiles' x,y coordinates.
+loops lists every closed loop of day 10 pipes with its length, the tile furthest round it and
+how many tiles it encloses, or just the loop through --start, measured from there.
+distances writes a heatmap of how far each tile on the day 10 loop is from 'S', as digits or a
+ppm image, or with --farthest prints the x,y coordinates of the tiles furthest away";
+
+fn get_day(day: u32) -> Result<&'static dyn Runner, Box<dyn Error>> {
+    solution::get(day).ok_or_else(|| format!("day {day} isn't implemented").into())
+}
+
+/// just the `--day`, if there is one, otherwise everything in the registry
+fn selected_days(args: &Args) -> Result<Vec<&'static dyn Runner>, Box<dyn Error>> {
+    match args.get_parsed("day")? {
+        Some(day) => Ok(vec![get_day(day)?]),
+        None => Ok(solution::registry()),
+    }
+}
+
+fn solve(day: u32, part: u32, data: &str) -> Result<String, Box<dyn Error>> {
+    get_day(day)?
+        .run(data, part)?
+        .ok_or_else(|| format!("day {day} has no part {part}").into())
+}
+
+fn verify_days(args: &Args) -> Result<(), Box<dyn Error>> {
+    let data_dir = Path::new(args.get("data-dir").unwrap_or("data"));
+
+    let mut checks = vec![];
+    for runner in selected_days(args)? {
+        for check in verify::verify_day(runner, data_dir) {
+            println!("{check}");
+            checks.push(check);
+        }
+    }
+
+    let summary = verify::summarize(&checks);
+    if summary.failed > 0 {
+        Err(summary.to_string().into())
+    } else {
+        println!("{summary}");
+        Ok(())
+    }
+}
+
+fn bench_days(args: &Args) -> Result<(), Box<dyn Error>> {
+    let data_dir = Path::new(args.get("data-dir").unwrap_or("data"));
+    let warmup = args.get_parsed("warmup")?.unwrap_or(3);
+    let iterations = args.get_parsed("iterations")?.unwrap_or(10);
+    let format = match args.get("format").unwrap_or("table") {
+        "table" => bench::to_table,
+        "csv" => bench::to_csv,
+        "json" => bench::to_json,
+        f => return Err(format!("unknown format '{f}'").into()),
+    };
+
+    let mut rows = vec![];
+    for runner in selected_days(args)? {
+        let fname = data_dir
+            .join(format!("day{}", runner.day()))
+            .join(verify::MAIN_INPUT);
+        let data = fs::read_to_string(&fname)
+            .map_err(|e| format!("couldn't read {}: {e}", fname.display()))?;
+
+        rows.extend(bench::bench_day(runner, &data, warmup, iterations)?);
+    }
+
+    print!("{}", format(&rows));
+    Ok(())
+}
+
+fn tilt_board(args: &Args) -> Result<(), Box<dyn Error>> {
+    let tilts = day14::parse_tilts(
+        args.get("sequence")
+            .ok_or("missing required flag --sequence")?,
+    )?;
+    let count = args.get_parsed("count")?.unwrap_or(1);
+    let highlight = match args.get("render") {
+        None => None,
+        Some("plain") => Some(Highlight::Plain),
+        Some("ansi") => Some(Highlight::Ansi),
+        Some("marked") => Some(Highlight::Marked),
+        Some(r) => return Err(format!("unknown render style '{r}'").into()),
+    };
+    let board = day14::parse_board(&args.read_input()?)?;
+
+    for (i, (dir, before, after)) in day14::run_tilts(&board, &tilts, count).enumerate() {
+        let load = day14::get_load(&after, Dir::North);
+        println!("{:>4}  {:<5}  {load}", i + 1, format!("{dir:?}"));
+        if let Some(highlight) = highlight {
+            println!("{}", day14::render(&before, &after, highlight));
+        }
+    }
+    Ok(())
+}
+
+fn load_series(args: &Args) -> Result<(), Box<dyn Error>> {
+    let cycles = args.required("cycles")?;
+    let side = match day14::parse_tilts(args.get("side").unwrap_or("N"))?[..] {
+        [side] => side,
+        _ => return Err("--side should be one of N, S, E or W".into()),
+    };
+    let board = day14::parse_board(&args.read_input()?)?;
+
+    let (loads, cycle) = day14::load_history(&board, side, cycles);
+    println!(
+        "# the boards loop from cycle {}, every {} cycles",
+        cycle.start,
+        cycle.len()
+    );
+    println!("cycle,load");
+    loads
+        .iter()
+        .enumerate()
+        .for_each(|(n, load)| println!("{n},{load}"));
+    Ok(())
+}
+
+fn unfold_springs(args: &Args) -> Result<(), Box<dyn Error>> {
+    let copies = args.required("copies")?;
+    let separator = match args.get("separator").unwrap_or("?") {
+        s @ ("." | "#" | "?") => s.chars().next().unwrap(),
+        s => return Err(format!("--separator should be '.', '#' or '?', not '{s}'").into()),
+    };
+    let lines = day12::parse_lines(&args.read_input()?)?;
+
+    println!("{}", day12::unfolded_total(&lines, copies, separator));
+    Ok(())
+}
+
+fn list_arrangements(args: &Args) -> Result<(), Box<dyn Error>> {
+    let row = args.get("row").ok_or("missing required flag --row")?;
+    let skip = args.get_parsed("skip")?.unwrap_or(0);
+    let limit = args.get_parsed("limit")?.unwrap_or(20);
+    let lines = day12::parse_lines(row)?;
+    let line = lines.first().ok_or("--row is empty")?;
+
+    let arrangements = day12::arrangements(line).ok_or("too many arrangements to list")?;
+    println!("# {} arrangements", arrangements.total());
+    arrangements
+        .skip(skip)
+        .take(limit)
+        .for_each(|a| println!("{a}"));
+    Ok(())
+}
+
+fn list_mirrors(args: &Args) -> Result<(), Box<dyn Error>> {
+    let smudges = args.get_parsed("smudges")?.unwrap_or(0);
+    let blocks = day13::parse_blocks(&args.read_input()?)?;
+
+    blocks.iter().enumerate().for_each(|(i, block)| {
+        let reflections = block.reflections(smudges);
+        let diagonals = block.diagonal_reflections(smudges);
+        let symmetries = block.symmetries();
+        if reflections.is_empty() && diagonals.is_empty() && symmetries.is_empty() {
+            println!("{i:>4}  none");
+        }
+        reflections.iter().for_each(|r| {
+            let (kind, after) = match r.mirror {
+                MirrorPoint::Row(n) => ("row", n),
+                MirrorPoint::Column(n) => ("column", n),
+            };
+            println!("{i:>4}  after {kind} {}, reflecting {}", after + 1, r.span);
+        });
+        diagonals.iter().for_each(|r| {
+            let (kind, (x, y)) = match r.mirror {
+                DiagonalMirror::Diagonal(pos) => ("diagonal", pos),
+                DiagonalMirror::AntiDiagonal(pos) => ("anti-diagonal", pos),
+            };
+            println!("{i:>4}  {kind} from {x},{y}, reflecting {}", r.span);
+        });
+        symmetries.iter().for_each(|s| println!("{i:>4}  {s:?}"));
+    });
+    Ok(())
+}
+
+fn draw_mirror(args: &Args) -> Result<(), Box<dyn Error>> {
+    let index: usize = args.required("block")?;
+    let smudges = args.get_parsed("smudges")?.unwrap_or(0);
+    let ansi = args.get_parsed("ansi")?.unwrap_or(false);
+    let blocks = day13::parse_blocks(&args.read_input()?)?;
+
+    let block = blocks
+        .get(index)
+        .ok_or_else(|| format!("there are only {} blocks", blocks.len()))?;
+    let mirror = block.mirror(smudges);
+    if mirror.is_none() {
+        println!("# no mirror with {smudges} smudges");
+    }
+    print!("{}", day13::render(block, mirror.as_ref(), ansi));
+    Ok(())
+}
+
+fn draw_pipes(args: &Args) -> Result<(), Box<dyn Error>> {
+    let data = day10::parse_grid(&args.read_input()?)?;
+
+    if args.get_parsed("list")?.unwrap_or(false) {
+        let mut tiles = day10::interior(&data).into_iter().collect::<Vec<_>>();
+        tiles.sort_by_key(|&(x, y)| (y, x));
+        tiles.iter().for_each(|(x, y)| println!("{x},{y}"));
+    } else {
+        print!("{}", day10::render(&data));
+    }
+    Ok(())
+}
+
+fn list_loops(args: &Args) -> Result<(), Box<dyn Error>> {
+    let grid = day10::parse_network(&args.read_input()?)?;
+
+    let loops = match args.get("start") {
+        Some(start) => {
+            let (x, y) = start
+                .split_once(',')
+                .and_then(|(x, y)| Some((x.parse().ok()?, y.parse().ok()?)))
+                .ok_or_else(|| format!("--start should look like x,y, not '{start}'"))?;
+            vec![day10::loop_from(&grid, (x, y))?]
+        }
+        None => day10::all_loops(&grid),
+    };
+
+    loops.iter().for_each(|l| {
+        let ((x, y), (fx, fy)) = (l.tiles[0], l.farthest);
+        println!(
+            "{x},{y}  {} tiles, farthest {fx},{fy} after {} steps, enclosing {}",
+            l.tiles.len(),
+            l.steps(),
+            l.enclosed
+        );
+    });
+    Ok(())
+}
+
+fn distance_map(args: &Args) -> Result<(), Box<dyn Error>> {
+    let heatmap = match args.get("format").unwrap_or("text") {
+        "text" => |map: &_| day10::heatmap_text(map).into_bytes(),
+        "ppm" => day10::heatmap_ppm,
+        f => return Err(format!("unknown format '{f}'").into()),
+    };
+    let data = day10::parse_grid(&args.read_input()?)?;
+    let map = day10::distances(&data);
+
+    if args.get_parsed("farthest")?.unwrap_or(false) {
+        let mut tiles = day10::farthest(&map).into_iter().collect::<Vec<_>>();
+        tiles.sort_by_key(|&(x, y)| (y, x));
+        tiles
+            .iter()
+            .for_each(|&(x, y)| println!("{x},{y}  {}", map[(x, y)].unwrap_or(0)));
+    } else {
+        io::stdout().write_all(&heatmap(&map))?;
+    }
+    Ok(())
+}
+
+fn run(args: &Args) -> Result<(), Box<dyn Error>> {
+    match args.command.as_deref() {
+        Some("run") => {
+            let day = args.required("day")?;
+            let part = args.required("part")?;
+            let data = args.read_input()?;
+
+            println!("{}", solve(day, part, &data)?);
+            Ok(())
+        }
+        Some("verify") => verify_days(args),
+        Some("bench") => bench_days(args),
+        Some("tilt") => tilt_board(args),
+        Some("loads") => load_series(args),
+        Some("unfold") => unfold_springs(args),
+        Some("arrangements") => list_arrangements(args),
+        Some("mirrors") => list_mirrors(args),
+        Some("mirror") => draw_mirror(args),
+        Some("pipes") => draw_pipes(args),
+        Some("loops") => list_loops(args),
+        Some("distances") => distance_map(args),
+        Some(cmd) => Err(format!("unknown command '{cmd}'\n\n{USAGE}").into()),
+        None => Err(USAGE.into()),
+    }
+}
 
 fn main() {
-    println!("Hello, world!");
+    let result = Args::parse(env::args().skip(1)).and_then(|args| run(&args));
+
+    if let Err(e) = result {
+        eprintln!("{e}");
+        process::exit(1);
+    }
 }
@@ -0,0 +1,138 @@
+use std::fmt::Display;
+use std::hint::black_box;
+use std::time::Duration;
+
+use crate::error::ParseError;
+use crate::util::timed;
+use crate::{
+    day1, day10, day11, day12, day13, day14, day2, day3, day4, day5, day6, day7, day8, day9,
+};
+
+/// a day's puzzle: parse the input once, then answer either part from the parsed form
+pub trait Solution {
+    const DAY: u32;
+
+    type Input<'a>;
+    type Answer1: Display;
+    type Answer2: Display;
+
+    fn parse(data: &str) -> Result<Self::Input<'_>, ParseError>;
+    /// anything part 1 needs from the input that part 2 doesn't, checked before solving it
+    fn check1(_input: &Self::Input<'_>) -> Result<(), ParseError> {
+        Ok(())
+    }
+    /// the same for part 2
+    fn check2(_input: &Self::Input<'_>) -> Result<(), ParseError> {
+        Ok(())
+    }
+    fn part1(input: &Self::Input<'_>) -> Self::Answer1;
+    fn part2(input: &Self::Input<'_>) -> Self::Answer2;
+}
+
+/// object-safe view of a [Solution], so days with different input and answer types can
+/// live in the same registry
+pub trait Runner {
+    fn day(&self) -> u32;
+    /// `None` if there's no such part
+    fn run(&self, data: &str, part: u32) -> Result<Option<String>, ParseError>;
+    /// parses then solves both parts once, timing each of those phases separately
+    fn time_phases(&self, data: &str) -> Result<[Duration; 3], ParseError>;
+}
+
+impl<S: Solution> Runner for S {
+    fn day(&self) -> u32 {
+        S::DAY
+    }
+
+    fn run(&self, data: &str, part: u32) -> Result<Option<String>, ParseError> {
+        Ok(match part {
+            1 => {
+                let input = S::parse(data)?;
+                S::check1(&input)?;
+                Some(S::part1(&input).to_string())
+            }
+            2 => {
+                let input = S::parse(data)?;
+                S::check2(&input)?;
+                Some(S::part2(&input).to_string())
+            }
+            _ => None,
+        })
+    }
+
+    fn time_phases(&self, data: &str) -> Result<[Duration; 3], ParseError> {
+        let (input, parse) = timed(|| S::parse(black_box(data)));
+        let input = input?;
+        S::check1(&input)?;
+        S::check2(&input)?;
+        let (answer1, part1) = timed(|| S::part1(&input));
+        let (answer2, part2) = timed(|| S::part2(&input));
+        black_box((answer1, answer2));
+
+        Ok([parse, part1, part2])
+    }
+}
+
+/// every implemented day, in order
+pub fn registry() -> Vec<&'static dyn Runner> {
+    vec![
+        &day1::Day1,
+        &day2::Day2,
+        &day3::Day3,
+        &day4::Day4,
+        &day5::Day5,
+        &day6::Day6,
+        &day7::Day7,
+        &day8::Day8,
+        &day9::Day9,
+        &day10::Day10,
+        &day11::Day11,
+        &day12::Day12,
+        &day13::Day13,
+        &day14::Day14,
+    ]
+}
+
+pub fn get(day: u32) -> Option<&'static dyn Runner> {
+    registry().into_iter().find(|r| r.day() == day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn t() {
+        let days: Vec<_> = registry().iter().map(|r| r.day()).collect();
+        assert_eq!(days, (1..=14).collect::<Vec<_>>());
+
+        assert!(get(15).is_none());
+
+        let day1 = get(1).unwrap();
+        assert_eq!(day1.run("1abc2\n", 1), Ok(Some(String::from("12"))));
+        assert_eq!(day1.run("1abc2\n", 3), Ok(None));
+
+        assert!(get(7).unwrap().run("32T3K\n", 1).is_err());
+    }
+
+    #[test]
+    fn non_ascii() {
+        // the odd character should be reported whole, not cut in half
+        [
+            (7, "32Té3 765\n", 3),
+            (8, "LéR\n", 1),
+            (10, "S-7\n|é|\nL-J\n", 1),
+            (11, "#.\n.é\n", 1),
+            (12, "é?.# 1\n", 0),
+            (13, "#.\n.é\n", 1),
+            (14, "O.\n.é\n", 1),
+        ]
+        .into_iter()
+        .for_each(|(day, data, column)| match get(day).unwrap().run(data, 1) {
+            Err(ParseError::Unexpected {
+                column: c, found, ..
+            }) => assert_eq!((c, found.as_str()), (column, "é"), "day {day}"),
+            other => panic!("day {day}: {other:?}"),
+        });
+    }
+}
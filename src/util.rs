@@ -1,12 +1,58 @@
+use std::fmt;
 use std::fmt::Formatter;
-use std::time::Instant;
-use std::{fmt, fs};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use crate::error::ParseError;
+
+mod big;
+mod bits;
+mod cycle;
+mod grid;
+mod parallel;
+pub use big::BigUint;
+pub use bits::Bits;
+pub use cycle::{find_cycle, nth_state, Cycle};
+pub use grid::{Dir, Grid, Pos};
+pub use parallel::par_map;
 
 pub struct DataLine<'a> {
     pub line: &'a str,
     pub line_number: usize,
 }
 
+impl<'a> DataLine<'a> {
+    /// byte offset of `part` in this line, or 0 if `part` isn't a slice of it
+    pub fn column_of(&self, part: &str) -> usize {
+        let start = self.line.as_ptr() as usize;
+        (part.as_ptr() as usize)
+            .checked_sub(start)
+            .filter(|&col| col <= self.line.len())
+            .unwrap_or(0)
+    }
+
+    /// an error for `found`, which should be a slice of this line so we can tell the column
+    pub fn unexpected(&self, found: &str, expected: &str) -> ParseError {
+        ParseError::Unexpected {
+            line_number: self.line_number,
+            column: self.column_of(found),
+            expected: String::from(expected),
+            found: String::from(found),
+        }
+    }
+
+    /// the whole character starting at byte `col`, as a slice of this line so errors about it
+    /// can tell the column
+    pub fn char_at(&self, col: usize) -> &'a str {
+        let len = self.line[col..].chars().next().map_or(0, char::len_utf8);
+        &self.line[col..col + len]
+    }
+
+    pub fn parse_num<T: FromStr>(&self, text: &'a str) -> Result<T, ParseError> {
+        text.parse().map_err(|_| self.unexpected(text, "a number"))
+    }
+}
+
 impl fmt::Display for DataLine<'_> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         f.write_fmt(format_args!("Line {}: ", self.line_number + 1))?;
@@ -15,30 +61,37 @@ impl fmt::Display for DataLine<'_> {
     }
 }
 
-pub fn get_non_empty_lines(data: &str) -> impl Iterator<Item = DataLine> {
-    data.split('\n')
-        .filter(|x| !x.is_empty())
-        .enumerate()
-        .map(|(line_number, line)| DataLine { line, line_number })
+pub fn get_non_empty_lines(data: &str) -> impl Iterator<Item = DataLine<'_>> {
+    get_lines(data).filter(|dl| !dl.line.is_empty())
 }
 
-pub fn get_lines(data: &str) -> impl Iterator<Item = DataLine> {
+pub fn get_lines(data: &str) -> impl Iterator<Item = DataLine<'_>> {
     data.split('\n')
         .zip(0..)
         .map(|(line, line_number)| DataLine { line, line_number })
 }
 
+#[cfg(test)]
 pub fn read_file_panic(fname: &str) -> String {
-    fs::read_to_string(fname).expect("couldn't read file")
+    std::fs::read_to_string(fname).expect("couldn't read file")
 }
 
-pub fn print_dur<F, R>(desc: &str, thunk: F) -> R
+pub fn timed<F, R>(thunk: F) -> (R, Duration)
 where
     F: FnOnce() -> R,
 {
     let start = Instant::now();
     let ret = thunk();
     let end = Instant::now();
-    println!("{desc} in {:?}", end.duration_since(start));
+    (ret, end.duration_since(start))
+}
+
+#[cfg(test)]
+pub fn print_dur<F, R>(desc: &str, thunk: F) -> R
+where
+    F: FnOnce() -> R,
+{
+    let (ret, dur) = timed(thunk);
+    println!("{desc} in {dur:?}");
     ret
 }
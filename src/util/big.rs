@@ -0,0 +1,122 @@
+use std::fmt;
+use std::fmt::Formatter;
+use std::iter::Sum;
+use std::ops::Add;
+
+/// an unsigned integer with no upper limit, for counts that outgrow a u64. only does what
+/// the puzzles need, which is adding and printing
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BigUint {
+    /// base 2^32 digits, least significant first, with no trailing zeroes
+    limbs: Vec<u32>,
+}
+
+impl BigUint {
+    pub fn to_u64(&self) -> Option<u64> {
+        match self.limbs[..] {
+            [] => Some(0),
+            [lo] => Some(lo as u64),
+            [lo, hi] => Some((hi as u64) << 32 | lo as u64),
+            _ => None,
+        }
+    }
+
+    /// divides in place, returning the remainder
+    fn div_rem_small(&mut self, divisor: u32) -> u32 {
+        let mut rem = 0u64;
+        self.limbs.iter_mut().rev().for_each(|limb| {
+            let curr = rem << 32 | *limb as u64;
+            *limb = (curr / divisor as u64) as u32;
+            rem = curr % divisor as u64;
+        });
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+        rem as u32
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(n: u64) -> Self {
+        let mut limbs = vec![n as u32, (n >> 32) as u32];
+        while limbs.last() == Some(&0) {
+            limbs.pop();
+        }
+        BigUint { limbs }
+    }
+}
+
+impl Add<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn add(self, other: &BigUint) -> BigUint {
+        let len = self.limbs.len().max(other.limbs.len());
+        let mut limbs = Vec::with_capacity(len + 1);
+        let mut carry = 0u64;
+        (0..len).for_each(|i| {
+            let a = self.limbs.get(i).copied().unwrap_or(0) as u64;
+            let b = other.limbs.get(i).copied().unwrap_or(0) as u64;
+            let sum = a + b + carry;
+            limbs.push(sum as u32);
+            carry = sum >> 32;
+        });
+        if carry > 0 {
+            limbs.push(carry as u32);
+        }
+        BigUint { limbs }
+    }
+}
+
+impl Add for BigUint {
+    type Output = BigUint;
+
+    fn add(self, other: BigUint) -> BigUint {
+        &self + &other
+    }
+}
+
+impl Sum for BigUint {
+    fn sum<I: Iterator<Item = BigUint>>(iter: I) -> Self {
+        iter.fold(BigUint::default(), |acc, n| acc + n)
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        const CHUNK: u32 = 1_000_000_000;
+
+        // peel off nine decimal digits at a time, least significant first
+        let mut n = self.clone();
+        let mut chunks = vec![];
+        loop {
+            chunks.push(n.div_rem_small(CHUNK));
+            if n.limbs.is_empty() {
+                break;
+            }
+        }
+
+        let mut chunks = chunks.iter().rev();
+        write!(f, "{}", chunks.next().unwrap())?;
+        chunks.try_for_each(|c| write!(f, "{c:09}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn t() {
+        assert_eq!(BigUint::default().to_string(), "0");
+        assert_eq!(BigUint::from(123).to_string(), "123");
+        assert_eq!(BigUint::from(u64::MAX).to_u64(), Some(u64::MAX));
+
+        let big = BigUint::from(u64::MAX) + BigUint::from(1);
+        assert_eq!(big.to_u64(), None);
+        assert_eq!(big.to_string(), "18446744073709551616");
+
+        let bigger: BigUint = (0..1000).map(|_| BigUint::from(u64::MAX)).sum();
+        assert_eq!(bigger.to_string(), "18446744073709551615000");
+        assert_eq!((&bigger + &bigger).to_string(), "36893488147419103230000");
+    }
+}
@@ -0,0 +1,52 @@
+/// a row of bits as long as it needs to be, packed 64 to a word
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Bits {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl Bits {
+    /// how many positions the two differ at, counting anything past the end of the shorter
+    /// one as unset
+    pub fn count_differences(&self, other: &Bits) -> u32 {
+        let len = self.words.len().max(other.words.len());
+        (0..len)
+            .map(|i| {
+                let a = self.words.get(i).copied().unwrap_or(0);
+                let b = other.words.get(i).copied().unwrap_or(0);
+                (a ^ b).count_ones()
+            })
+            .sum()
+    }
+}
+
+impl FromIterator<bool> for Bits {
+    fn from_iter<I: IntoIterator<Item = bool>>(iter: I) -> Self {
+        let mut bits = Bits::default();
+        iter.into_iter().for_each(|b| {
+            if bits.len % 64 == 0 {
+                bits.words.push(0);
+            }
+            if b {
+                bits.words[bits.len / 64] |= 1 << (bits.len % 64);
+            }
+            bits.len += 1;
+        });
+        bits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn t() {
+        let a = (0..150).map(|i| i % 3 == 0).collect::<Bits>();
+        let b = (0..150).map(|i| i % 3 == 0 || i == 140).collect::<Bits>();
+        assert_eq!(a.count_differences(&a), 0);
+        assert_eq!(a.count_differences(&b), 1);
+        assert_ne!(a, b);
+        assert_eq!(Bits::default().count_differences(&a), 50);
+    }
+}
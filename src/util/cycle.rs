@@ -0,0 +1,80 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// the states a simulation goes through from its initial state, up to the point where it
+/// starts repeating itself
+#[derive(Debug)]
+pub struct Cycle<T> {
+    /// every distinct state, in the order they were reached, starting with the initial one
+    pub states: Vec<T>,
+    /// index into `states` of the first state that's part of the loop
+    pub start: usize,
+}
+
+impl<T> Cycle<T> {
+    pub fn len(&self) -> usize {
+        self.states.len() - self.start
+    }
+
+    /// the state after `n` steps, however large `n` is
+    pub fn nth(&self, n: usize) -> &T {
+        if n < self.start {
+            &self.states[n]
+        } else {
+            &self.states[self.start + (n - self.start) % self.len()]
+        }
+    }
+}
+
+/// keeps applying `step` until a state comes up a second time. `step` has to be
+/// deterministic, and the simulation has to eventually repeat, or this never returns
+pub fn find_cycle<T, F>(init: T, mut step: F) -> Cycle<T>
+where
+    T: Clone + Eq + Hash,
+    F: FnMut(&T) -> T,
+{
+    let mut seen = HashMap::new();
+    let mut states = vec![];
+    let mut curr = init;
+
+    loop {
+        if let Some(&start) = seen.get(&curr) {
+            return Cycle { states, start };
+        }
+        seen.insert(curr.clone(), states.len());
+        let next = step(&curr);
+        states.push(curr);
+        curr = next;
+    }
+}
+
+/// the state after `n` steps, without having to actually take all of them
+pub fn nth_state<T, F>(init: T, step: F, n: usize) -> T
+where
+    T: Clone + Eq + Hash,
+    F: FnMut(&T) -> T,
+{
+    find_cycle(init, step).nth(n).clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn t() {
+        // 3, 4, 5, 6, 7, 8, 9, then back to 5
+        let step = |&x: &u32| if x == 9 { 5 } else { x + 1 };
+
+        let cycle = find_cycle(3, step);
+        assert_eq!((cycle.start, cycle.len()), (2, 5));
+        assert_eq!(*cycle.nth(0), 3);
+        assert_eq!(*cycle.nth(6), 9);
+        assert_eq!(*cycle.nth(7), 5);
+        assert_eq!(*cycle.nth(1_000_000_000), 5 + (1_000_000_000 - 2) % 5);
+
+        assert_eq!(nth_state(3, step, 4), 7);
+        assert_eq!(nth_state(3, step, 1_000_000_000), *cycle.nth(1_000_000_000));
+        assert_eq!(nth_state(5, |&x| x, 10), 5);
+    }
+}
@@ -0,0 +1,315 @@
+use std::fmt;
+use std::fmt::Formatter;
+use std::ops::{Index, IndexMut};
+
+use crate::error::ParseError;
+use crate::util::{get_non_empty_lines, DataLine};
+
+/// (x, y), with (0, 0) in the top left
+pub type Pos = (usize, usize);
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Dir {
+    North,
+    South,
+    East,
+    West,
+}
+
+impl Dir {
+    pub const ALL: [Dir; 4] = [Dir::North, Dir::South, Dir::East, Dir::West];
+
+    pub fn opposite(self) -> Dir {
+        match self {
+            Dir::North => Dir::South,
+            Dir::South => Dir::North,
+            Dir::East => Dir::West,
+            Dir::West => Dir::East,
+        }
+    }
+}
+
+/// a rectangle of cells stored row by row in one vec
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid {
+            cells: vec![fill; width * height],
+            width,
+            height,
+        }
+    }
+
+    /// `None` if the rows aren't all the same length
+    #[allow(dead_code)]
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Option<Grid<T>> {
+        let height = rows.len();
+        let width = rows.first().map_or(0, |r| r.len());
+        if rows.iter().any(|r| r.len() != width) {
+            return None;
+        }
+
+        Some(Grid {
+            cells: rows.into_iter().flatten().collect(),
+            width,
+            height,
+        })
+    }
+
+    /// builds a grid from lines of text, turning each char into a cell with `cell`, which
+    /// also gets the line and column for error reporting
+    pub fn parse_lines<'a, I, F>(lines: I, mut cell: F) -> Result<Grid<T>, ParseError>
+    where
+        I: IntoIterator<Item = DataLine<'a>>,
+        F: FnMut(&DataLine<'a>, usize, char) -> Result<T, ParseError>,
+    {
+        let mut cells = vec![];
+        let mut width = None;
+        let mut height = 0;
+
+        for line in lines {
+            let row_width = line.line.chars().count();
+            if *width.get_or_insert(row_width) != row_width {
+                let expected = format!("{} cells", width.unwrap());
+                return Err(line.unexpected(line.line, &expected));
+            }
+
+            for (col, c) in line.line.char_indices() {
+                cells.push(cell(&line, col, c)?);
+            }
+            height += 1;
+        }
+
+        Ok(Grid {
+            cells,
+            width: width.unwrap_or(0),
+            height,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn in_bounds(&self, (x, y): Pos) -> bool {
+        x < self.width && y < self.height
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        self.in_bounds(pos)
+            .then(|| &self.cells[pos.1 * self.width + pos.0])
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        if self.in_bounds(pos) {
+            Some(&mut self.cells[pos.1 * self.width + pos.0])
+        } else {
+            None
+        }
+    }
+
+    pub fn swap(&mut self, a: Pos, b: Pos) {
+        self.cells
+            .swap(a.1 * self.width + a.0, b.1 * self.width + b.0);
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).map(|y| self.row(y))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        self.cells[x..].iter().step_by(self.width.max(1))
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|x| self.column(x))
+    }
+
+    /// every position, row by row
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    /// the neighboring position in `dir`, if it's still on the grid
+    pub fn step(&self, (x, y): Pos, dir: Dir) -> Option<Pos> {
+        let next = match dir {
+            Dir::North => (x, y.checked_sub(1)?),
+            Dir::South => (x, y + 1),
+            Dir::East => (x + 1, y),
+            Dir::West => (x.checked_sub(1)?, y),
+        };
+        Some(next).filter(|&p| self.in_bounds(p))
+    }
+
+    #[allow(dead_code)]
+    pub fn neighbors4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        Dir::ALL.into_iter().filter_map(move |d| self.step(pos, d))
+    }
+
+    /// includes the diagonals
+    pub fn neighbors8(&self, (x, y): Pos) -> impl Iterator<Item = Pos> + '_ {
+        (-1..=1)
+            .flat_map(|dy| (-1..=1).map(move |dx| (dx, dy)))
+            .filter(|&d| d != (0, 0))
+            .filter_map(move |(dx, dy)| {
+                let nx = x.checked_add_signed(dx)?;
+                let ny = y.checked_add_signed(dy)?;
+                Some((nx, ny)).filter(|&p| self.in_bounds(p))
+            })
+    }
+
+    #[allow(dead_code)]
+    pub fn map<U, F>(&self, f: F) -> Grid<U>
+    where
+        F: FnMut(&T) -> U,
+    {
+        Grid {
+            cells: self.cells.iter().map(f).collect(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    fn from_fn<F>(width: usize, height: usize, mut f: F) -> Grid<T>
+    where
+        F: FnMut(Pos) -> T,
+    {
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(&mut f)
+            .collect();
+        Grid {
+            cells,
+            width,
+            height,
+        }
+    }
+
+    /// rows become columns
+    #[allow(dead_code)]
+    pub fn transpose(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid::from_fn(self.height, self.width, |(x, y)| self[(y, x)].clone())
+    }
+
+    pub fn rotate_clockwise(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        let h = self.height;
+        Grid::from_fn(self.height, self.width, |(x, y)| {
+            self[(y, h - 1 - x)].clone()
+        })
+    }
+
+    #[allow(dead_code)]
+    pub fn rotate_counter_clockwise(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        let w = self.width;
+        Grid::from_fn(self.height, self.width, |(x, y)| {
+            self[(w - 1 - y, x)].clone()
+        })
+    }
+}
+
+impl Grid<char> {
+    /// every non-empty line is a row
+    pub fn parse(data: &str) -> Result<Grid<char>, ParseError> {
+        Grid::parse_lines(get_non_empty_lines(data), |_, _, c| Ok(c))
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        self.get(pos)
+            .unwrap_or_else(|| panic!("{pos:?} is outside the grid"))
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("{pos:?} is outside the grid"))
+    }
+}
+
+impl fmt::Display for Grid<char> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.rows().try_for_each(|row| {
+            row.iter().try_for_each(|c| write!(f, "{c}"))?;
+            writeln!(f)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid() -> Grid<char> {
+        Grid::parse("abc\ndef\n").unwrap()
+    }
+
+    #[test]
+    fn t() {
+        let g = grid();
+        assert_eq!((g.width(), g.height()), (3, 2));
+        assert_eq!(g[(1, 1)], 'e');
+        assert_eq!(g.get((3, 0)), None);
+        assert_eq!(g.row(1), ['d', 'e', 'f']);
+        assert_eq!(g.column(2).collect::<String>(), "cf");
+        assert_eq!(g.to_string(), "abc\ndef\n");
+
+        assert_eq!(g.transpose().to_string(), "ad\nbe\ncf\n");
+        assert_eq!(g.rotate_clockwise().to_string(), "da\neb\nfc\n");
+        assert_eq!(g.rotate_counter_clockwise().to_string(), "cf\nbe\nad\n");
+        assert_eq!(g.rotate_clockwise().rotate_counter_clockwise(), g);
+
+        assert_eq!(Grid::from_rows(vec![vec![1, 2], vec![3]]), None);
+        assert!(Grid::parse("ab\nc\n").is_err());
+    }
+
+    #[test]
+    fn t_neighbors() {
+        let g = grid();
+        assert_eq!(g.step((0, 0), Dir::North), None);
+        assert_eq!(g.step((0, 0), Dir::South), Some((0, 1)));
+        assert_eq!(g.step((0, 1), Dir::South), None);
+
+        let mut n4 = g.neighbors4((1, 0)).collect::<Vec<_>>();
+        n4.sort();
+        assert_eq!(n4, vec![(0, 0), (1, 1), (2, 0)]);
+
+        let mut n8 = g.neighbors8((0, 1)).collect::<Vec<_>>();
+        n8.sort();
+        assert_eq!(n8, vec![(0, 0), (1, 0), (1, 1)]);
+    }
+}
@@ -0,0 +1,239 @@
+use std::fmt;
+use std::fmt::Formatter;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::error::ParseError;
+use crate::solution::Runner;
+use crate::util::{get_non_empty_lines, timed};
+
+/// lives in each day's data directory, one `<input file> <part> <answer>` per line, where the
+/// input file is relative to that directory
+pub const ANSWERS_FILE: &str = "answers.txt";
+/// the personal puzzle input, which should have an answer recorded for both parts
+pub const MAIN_INPUT: &str = "data.txt";
+
+#[derive(Debug, PartialEq)]
+pub struct Expected<'a> {
+    pub input: &'a str,
+    pub part: u32,
+    pub answer: &'a str,
+}
+
+pub fn parse_answers(data: &str) -> Result<Vec<Expected<'_>>, ParseError> {
+    get_non_empty_lines(data)
+        .map(|line| {
+            let fields: Vec<_> = line.line.split_whitespace().collect();
+            match fields[..] {
+                [input, part, answer] => {
+                    let part = line.parse_num(part)?;
+                    if part != 1 && part != 2 {
+                        return Err(line.unexpected(fields[1], "part 1 or 2"));
+                    }
+                    Ok(Expected {
+                        input,
+                        part,
+                        answer,
+                    })
+                }
+                _ => Err(line.unexpected(line.line, "<input file> <part> <answer>")),
+            }
+        })
+        .collect()
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Outcome {
+    Pass,
+    Fail { expected: String, actual: String },
+    Missing(String),
+    Error(String),
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Pass => write!(f, "pass"),
+            Outcome::Fail { expected, actual } => {
+                write!(f, "FAIL    expected {expected}, got {actual}")
+            }
+            Outcome::Missing(why) => write!(f, "missing {why}"),
+            Outcome::Error(e) => write!(f, "ERROR   {e}"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Check {
+    pub day: u32,
+    pub input: String,
+    pub part: Option<u32>,
+    pub outcome: Outcome,
+    pub elapsed: Option<Duration>,
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let part = self.part.map_or(String::new(), |p| format!("part {p}"));
+        let elapsed = self.elapsed.map_or(String::new(), |e| format!("{e:.2?}"));
+        write!(
+            f,
+            "day {:>2}  {:<16} {:<6}  {:>10}  {}",
+            self.day, self.input, part, elapsed, self.outcome
+        )
+    }
+}
+
+fn run_check(runner: &dyn Runner, day_dir: &Path, expected: &Expected) -> Check {
+    let check = |outcome, elapsed| Check {
+        day: runner.day(),
+        input: String::from(expected.input),
+        part: Some(expected.part),
+        outcome,
+        elapsed,
+    };
+
+    let data = match fs::read_to_string(day_dir.join(expected.input)) {
+        Ok(data) => data,
+        Err(e) => return check(Outcome::Missing(format!("couldn't read input: {e}")), None),
+    };
+
+    let (result, elapsed) = timed(|| runner.run(&data, expected.part));
+    let outcome = match result {
+        Ok(Some(actual)) if actual == expected.answer => Outcome::Pass,
+        Ok(Some(actual)) => Outcome::Fail {
+            expected: String::from(expected.answer),
+            actual,
+        },
+        Ok(None) => Outcome::Error(format!("no part {}", expected.part)),
+        Err(e) => Outcome::Error(e.to_string()),
+    };
+
+    check(outcome, Some(elapsed))
+}
+
+/// runs every answer recorded for this day, and reports any part of the main input that
+/// doesn't have one
+pub fn verify_day(runner: &dyn Runner, data_dir: &Path) -> Vec<Check> {
+    let day = runner.day();
+    let day_dir = data_dir.join(format!("day{day}"));
+
+    // no answers file just means every part is missing
+    let answers = fs::read_to_string(day_dir.join(ANSWERS_FILE)).unwrap_or_default();
+    let expected = match parse_answers(&answers) {
+        Ok(expected) => expected,
+        Err(e) => {
+            return vec![Check {
+                day,
+                input: String::from(ANSWERS_FILE),
+                part: None,
+                outcome: Outcome::Error(e.to_string()),
+                elapsed: None,
+            }]
+        }
+    };
+
+    let mut checks: Vec<_> = expected
+        .iter()
+        .map(|e| run_check(runner, &day_dir, e))
+        .collect();
+
+    (1..=2)
+        .filter(|&part| {
+            !expected
+                .iter()
+                .any(|e| e.input == MAIN_INPUT && e.part == part)
+        })
+        .for_each(|part| {
+            checks.push(Check {
+                day,
+                input: String::from(MAIN_INPUT),
+                part: Some(part),
+                outcome: Outcome::Missing(String::from("no recorded answer")),
+                elapsed: None,
+            })
+        });
+
+    checks
+}
+
+/// what the answers file says the main input's answer to `part` is, so tests don't have to
+/// hard-code it
+#[cfg(test)]
+pub fn recorded_answer(day: u32, part: u32) -> String {
+    let answers = crate::util::read_file_panic(&format!("./data/day{day}/{ANSWERS_FILE}"));
+    parse_answers(&answers)
+        .expect("couldn't parse the answers file")
+        .into_iter()
+        .find(|e| e.input == MAIN_INPUT && e.part == part)
+        .map(|e| String::from(e.answer))
+        .unwrap_or_else(|| panic!("no answer recorded for day {day} part {part}"))
+}
+
+pub struct Summary {
+    pub passed: usize,
+    pub failed: usize,
+    pub missing: usize,
+}
+
+pub fn summarize(checks: &[Check]) -> Summary {
+    let count = |f: fn(&Outcome) -> bool| checks.iter().filter(|c| f(&c.outcome)).count();
+
+    Summary {
+        passed: count(|o| matches!(o, Outcome::Pass)),
+        failed: count(|o| matches!(o, Outcome::Fail { .. } | Outcome::Error(_))),
+        missing: count(|o| matches!(o, Outcome::Missing(_))),
+    }
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} passed, {} failed, {} missing",
+            self.passed, self.failed, self.missing
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::solution;
+
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let answers = parse_answers("data.txt 1 55002\n\npart2/test.txt 2 281\n").unwrap();
+        assert_eq!(
+            answers,
+            vec![
+                Expected {
+                    input: "data.txt",
+                    part: 1,
+                    answer: "55002"
+                },
+                Expected {
+                    input: "part2/test.txt",
+                    part: 2,
+                    answer: "281"
+                }
+            ]
+        );
+
+        assert!(parse_answers("data.txt 55002\n").is_err());
+        assert!(parse_answers("data.txt 3 55002\n").is_err());
+    }
+
+    #[test]
+    fn t() {
+        let checks = verify_day(solution::get(1).unwrap(), Path::new("./data"));
+        assert_eq!(checks.len(), 4);
+        assert!(checks.iter().all(|c| c.outcome == Outcome::Pass));
+
+        let checks = verify_day(solution::get(1).unwrap(), Path::new("./nowhere"));
+        let summary = summarize(&checks);
+        assert_eq!((summary.passed, summary.failed, summary.missing), (0, 0, 2));
+    }
+}